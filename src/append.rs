@@ -0,0 +1,50 @@
+use crate::error::Error;
+
+/// Inserts the text right before the line of the module where the section
+/// begins. Sections are located by their first non-empty line.
+pub fn insert_before(module: &str, section: &str, text: &str) -> Result<String, Error> {
+    if text.is_empty() {
+        return Ok(module.to_owned());
+    }
+
+    let anchor = section
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| Error::Append("empty section cannot be located".to_owned()))?;
+
+    let mut offset = 0;
+    for line in module.split_inclusive('\n') {
+        if line.trim() == anchor {
+            return Ok([&module[..offset], text, &module[offset..]].concat());
+        }
+
+        offset += line.len();
+    }
+
+    Err(Error::Append(format!(
+        "`{anchor}` is not found in the existing module"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_before_section() {
+        let module = "mod erc20 {\n    const A: u8 = 1;\n\n    use ethabi::Token;\n}\n";
+        let inserted = insert_before(
+            module,
+            "\n    use ethabi::Token;\n",
+            "    const B: u8 = 2;\n\n",
+        );
+
+        assert_eq!(
+            inserted.unwrap(),
+            "mod erc20 {\n    const A: u8 = 1;\n\n    const B: u8 = 2;\n\n    use ethabi::Token;\n}\n"
+        );
+
+        assert!(insert_before(module, "impl Erc20 {", "    fn b() {}\n").is_err());
+    }
+}
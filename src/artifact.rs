@@ -0,0 +1,343 @@
+use crate::{error::Error, human};
+use itertools::Itertools;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    io::{BufRead, Read},
+};
+
+/// Top level keys the ABI is found under: Hardhat, Foundry and Truffle artifacts
+/// keep it in `abi`, solc metadata in `output.abi`, `solc --combined-json`
+/// output in `contracts.<path>:<name>.abi` and solc standard JSON output in
+/// `contracts.<path>.<name>.abi`
+const ABI_KEYS: &[&str] = &["abi", "output", "contracts"];
+
+/// Incremental scanner of a top level JSON object that captures raw values
+/// of the requested keys without parsing (or even keeping) the rest of the
+/// document. Build artifacts are dominated by bytecode and source maps that
+/// we are not interested in at all.
+struct Scanner<'k> {
+    keys: &'k [&'k str],
+
+    /// Stop at the first key found instead of waiting for all of them
+    first_only: bool,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+
+    /// Next string at the top level would be a key
+    expect_key: bool,
+    collecting_key: bool,
+    key: Vec<u8>,
+    last_key: String,
+
+    capture: Option<Vec<u8>>,
+    sections: HashMap<String, String>,
+}
+
+impl<'k> Scanner<'k> {
+    fn new(keys: &'k [&'k str], first_only: bool) -> Self {
+        Scanner {
+            keys,
+            first_only,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            expect_key: false,
+            collecting_key: false,
+            key: Vec::new(),
+            last_key: String::new(),
+            capture: None,
+            sections: HashMap::new(),
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.sections.len() == self.keys.len() || (self.first_only && !self.sections.is_empty())
+    }
+
+    fn feed(&mut self, byte: u8) {
+        if let Some(buffer) = self.capture.as_mut() {
+            buffer.push(byte);
+        }
+
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+
+                if self.collecting_key {
+                    self.collecting_key = false;
+                    self.last_key = String::from_utf8_lossy(&self.key).into_owned();
+                }
+
+                return;
+            }
+
+            if self.collecting_key {
+                self.key.push(byte);
+            }
+
+            return;
+        }
+
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                self.collecting_key = self.depth == 1 && self.expect_key;
+                self.key.clear();
+            }
+
+            b'{' | b'[' => {
+                self.depth += 1;
+                self.expect_key = self.depth == 1 && byte == b'{';
+            }
+
+            b'}' | b']' => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    self.finish_capture();
+                }
+            }
+
+            b',' if self.depth == 1 => {
+                self.finish_capture();
+                self.expect_key = true;
+            }
+
+            b':' if self.depth == 1 => {
+                self.expect_key = false;
+                if self.keys.contains(&self.last_key.as_str()) {
+                    self.capture = Some(Vec::new());
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn finish_capture(&mut self) {
+        if let Some(mut buffer) = self.capture.take() {
+            // Drop the terminating `,` or `}`
+            buffer.pop();
+
+            let value = String::from_utf8_lossy(&buffer).trim().to_owned();
+            self.sections.insert(self.last_key.clone(), value);
+        }
+    }
+}
+
+/// Reads the top level object from the reader, returning raw JSON values of requested keys.
+/// Reading stops as soon as all of the keys were found.
+pub fn extract_sections(
+    reader: &mut dyn BufRead,
+    keys: &[&str],
+) -> Result<HashMap<String, String>, Error> {
+    scan(reader, Scanner::new(keys, false))
+}
+
+/// Reads the top level object until any of the keys is found, returning the key and its raw value
+fn extract_first(
+    reader: &mut dyn BufRead,
+    keys: &[&str],
+) -> Result<Option<(String, String)>, Error> {
+    Ok(scan(reader, Scanner::new(keys, true))?.into_iter().next())
+}
+
+fn scan(reader: &mut dyn BufRead, mut scanner: Scanner) -> Result<HashMap<String, String>, Error> {
+    while !scanner.done() {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        let mut consumed = 0;
+        for &byte in chunk {
+            consumed += 1;
+            scanner.feed(byte);
+
+            if scanner.done() {
+                break;
+            }
+        }
+
+        reader.consume(consumed);
+    }
+
+    Ok(scanner.sections)
+}
+
+/// Skips leading whitespace and checks whether the input is a JSON object
+fn starts_with_object(reader: &mut dyn BufRead) -> Result<bool, Error> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+
+        match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(position) => {
+                let first = buffer[position];
+                reader.consume(position);
+                return Ok(first == b'{');
+            }
+
+            None => {
+                let length = buffer.len();
+                reader.consume(length);
+            }
+        }
+    }
+}
+
+/// Reads ABI either as is, or from a build artifact
+pub fn read_abi(reader: &mut dyn BufRead) -> Result<String, Error> {
+    read_contract_abi(reader, None)
+}
+
+/// Reads ABI either as is, or from a build artifact. Contract name selects
+/// one of the contracts when the input describes several of them.
+pub fn read_contract_abi(
+    reader: &mut dyn BufRead,
+    contract: Option<&str>,
+) -> Result<String, Error> {
+    if !starts_with_object(reader)? {
+        let mut buffer = String::new();
+        reader.read_to_string(&mut buffer)?;
+        return human::read(buffer);
+    }
+
+    match extract_first(reader, ABI_KEYS)? {
+        Some((key, value)) if key == "abi" => Ok(value),
+        Some((key, value)) if key == "output" => {
+            abi_value(&serde_json::from_str::<Value>(&value)?["abi"])
+        }
+        Some((_, value)) => combined_abi(&serde_json::from_str(&value)?, contract),
+        None => Err(Error::Metadata(
+            "input object does not contain 'abi' section".to_owned(),
+        )),
+    }
+}
+
+/// ABI array, which older compilers put into JSON as a string
+fn abi_value(value: &Value) -> Result<String, Error> {
+    match value {
+        Value::String(abi) => Ok(abi.clone()),
+        Value::Array(_) => Ok(value.to_string()),
+        _ => Err(Error::Metadata("input does not contain ABI".to_owned())),
+    }
+}
+
+/// ABI of the contract from the `contracts` section of the combined JSON,
+/// where contracts are named `<path>:<name>`, or of the standard JSON output,
+/// where contracts are grouped by their source path
+fn combined_abi(contracts: &Value, contract: Option<&str>) -> Result<String, Error> {
+    let matching = contracts
+        .as_object()
+        .into_iter()
+        .flatten()
+        .flat_map(|(name, item)| match item.as_object() {
+            Some(source) if !source.contains_key("abi") => source
+                .iter()
+                .map(|(contract, item)| (format!("{name}:{contract}"), item))
+                .collect_vec(),
+            _ => vec![(name.clone(), item)],
+        })
+        .filter(|(name, _)| {
+            contract.map_or(true, |contract| {
+                name == contract || name.rsplit(':').next() == Some(contract)
+            })
+        })
+        .collect_vec();
+
+    match matching.as_slice() {
+        [(_, item)] => abi_value(&item["abi"]),
+        [] => Err(Error::Metadata(format!(
+            "contract {} not found in the input",
+            contract.unwrap_or_default()
+        ))),
+        _ => Err(Error::Metadata(format!(
+            "input contains several contracts, select one of them: {}",
+            matching.iter().map(|(name, _)| name).join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_requested_sections() {
+        let artifact = r#"{
+            "contractName": "Token",
+            "abi": [{"type": "function", "name": "a,b}"}],
+            "bytecode": {"object": "0x6080"},
+            "deployedBytecode": "0x6080"
+        }"#;
+
+        let sections = extract_sections(&mut artifact.as_bytes(), &["abi", "bytecode"]).unwrap();
+
+        assert_eq!(sections["abi"], r#"[{"type": "function", "name": "a,b}"}]"#);
+        assert_eq!(sections["bytecode"], r#"{"object": "0x6080"}"#);
+    }
+
+    #[test]
+    fn ignores_nested_keys() {
+        let artifact = r#"{"metadata": {"abi": 1}, "abi": []}"#;
+        let sections = extract_sections(&mut artifact.as_bytes(), &["abi"]).unwrap();
+
+        assert_eq!(sections["abi"], "[]");
+    }
+
+    #[test]
+    fn reads_abi_of_combined_json() {
+        let combined = r#"{
+            "contracts": {
+                "a.sol:A": {"abi": "[]"},
+                "b.sol:B": {"abi": [{"type": "function"}]}
+            },
+            "version": "0.8.17"
+        }"#;
+
+        assert_eq!(
+            read_contract_abi(&mut combined.as_bytes(), Some("B")).unwrap(),
+            r#"[{"type":"function"}]"#
+        );
+        assert!(read_abi(&mut combined.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn reads_abi_of_standard_json() {
+        let output = r#"{
+            "contracts": {
+                "src/Token.sol": {
+                    "IERC20": {"abi": []},
+                    "Token": {"abi": [{"type": "function"}], "evm": {}}
+                }
+            },
+            "sources": {}
+        }"#;
+
+        assert_eq!(
+            read_contract_abi(&mut output.as_bytes(), Some("src/Token.sol:Token")).unwrap(),
+            r#"[{"type":"function"}]"#
+        );
+        assert!(matches!(
+            read_abi(&mut output.as_bytes()),
+            Err(Error::Metadata(message)) if message.contains("src/Token.sol:IERC20")
+        ));
+    }
+
+    #[test]
+    fn passes_bare_abi_through() {
+        let abi = r#" [{"type": "function"}]"#;
+        assert_eq!(
+            read_abi(&mut abi.as_bytes()).unwrap(),
+            r#"[{"type": "function"}]"#
+        );
+    }
+}
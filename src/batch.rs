@@ -0,0 +1,375 @@
+use crate::{cache::Cache, error::Error, ident, input, keep::Regions, manifest, migration};
+use rayon::prelude::*;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Extensions of files that are picked up from the input directory
+const INPUT_EXTENSIONS: &[&str] = &["json", "abi"];
+
+/// File declaring the generated Rust modules, next to them
+pub const MOD_FILE: &str = "mod.rs";
+
+pub struct Options<'a> {
+    pub input_dir: &'a Path,
+    pub output_dir: &'a Path,
+
+    /// Extension of generated files, i.e. `rs`
+    pub extension: &'a str,
+
+    /// Pattern of output paths relative to the output directory, see [`output_path`]
+    pub path_pattern: Option<&'a str>,
+
+    /// Number of worker threads, all available CPUs if `None`
+    pub jobs: Option<usize>,
+
+    /// Summary of generation options. Outputs produced with
+    /// a different fingerprint are never considered fresh.
+    pub fingerprint: String,
+
+    /// Whether to skip inputs that were not changed since the last run
+    pub use_cache: bool,
+
+    /// Whether to declare the generated modules in [`MOD_FILE`]
+    pub mod_file: bool,
+
+    /// Whether to delete files generated from inputs that were removed since
+    pub prune: bool,
+}
+
+enum Outcome {
+    Rendered { key: String, notes: Option<String> },
+    Fresh,
+}
+
+/// Whether the input is a glob pattern rather than a directory, i.e. `abis/**/*.json`
+fn is_pattern(input: &Path) -> bool {
+    input.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Collects input files from the directory, or the files matching the glob
+/// pattern, in a stable (sorted) order
+pub fn collect_inputs(input_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    if is_pattern(input_dir) {
+        let mut inputs = Vec::new();
+        for entry in glob::glob(&input_dir.to_string_lossy())? {
+            let path = entry.map_err(|e| Error::ReadInput {
+                path: e.path().to_owned(),
+                inner: e.into_error(),
+            })?;
+
+            if path.is_file() {
+                inputs.push(path);
+            }
+        }
+
+        inputs.sort();
+        return Ok(inputs);
+    }
+
+    let entries = fs::read_dir(input_dir).map_err(|e| Error::ReadInput {
+        path: input_dir.to_owned(),
+        inner: e,
+    })?;
+
+    let mut inputs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_input = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| INPUT_EXTENSIONS.contains(&ext));
+
+        if path.is_file() && is_input {
+            inputs.push(path);
+        }
+    }
+
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Derives module name from the input file name, i.e. `IERC20.json` -> `ierc20`
+pub fn module_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| ident::snake_case(&stem.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+/// Path of the module in the output directory: the pattern with `{name}` replaced
+/// by the module name, or `<name>.<extension>` without a pattern
+pub fn output_path(
+    output_dir: &Path,
+    pattern: Option<&str>,
+    module_name: &str,
+    extension: &str,
+) -> PathBuf {
+    match pattern {
+        Some(pattern) => output_dir.join(pattern.replace("{name}", module_name)),
+        None => output_dir.join(module_name).with_extension(extension),
+    }
+}
+
+/// Creates the directory of the output file
+pub fn create_parent(output: &Path) -> Result<(), Error> {
+    match output.parent() {
+        Some(dir) => fs::create_dir_all(dir).map_err(|e| Error::WriteOutput {
+            path: dir.to_owned(),
+            inner: e,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Fails if several inputs, given as `(module name, input)` pairs,
+/// would be generated into the same module and overwrite each other
+pub fn ensure_unique<I>(modules: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut seen = HashMap::new();
+    for (name, input) in modules {
+        if let Some(first) = seen.insert(name.clone(), input.clone()) {
+            return Err(Error::DuplicateModule {
+                name,
+                first,
+                second: input,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes [`MOD_FILE`] declaring every module, so the output directory
+/// can be included in a crate with a single `mod` item
+pub fn write_mod_file(output_dir: &Path, module_names: &[String]) -> Result<(), Error> {
+    let mut contents = String::from("//! This file was autogenerated by Sumi\n\n");
+    for name in module_names {
+        contents.push_str(&format!("pub mod {name};\n"));
+    }
+
+    let path = output_dir.join(MOD_FILE);
+    fs::write(&path, contents).map_err(|e| Error::WriteOutput { path, inner: e })
+}
+
+/// Writes rendered output to the file, carrying over manually written regions.
+/// Partially written output is removed if rendering fails.
+pub fn write_output<F>(output: &Path, render: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+{
+    let regions = Regions::load(output)?;
+    let file = fs::File::create(output).map_err(|e| Error::WriteOutput {
+        path: output.to_owned(),
+        inner: e,
+    })?;
+
+    let mut writer = BufWriter::new(file);
+    let result = regions
+        .render(&mut writer, render)
+        .and_then(|_| Ok(writer.flush()?));
+
+    drop(writer);
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+
+    result
+}
+
+/// Renders every input file of the directory into its own module.
+///
+/// Inputs are parsed and rendered in parallel, but the results are written
+/// in the original (sorted) order, so the output is deterministic.
+///
+/// Every worker thread keeps its own renderer state `T` (i.e. compiled
+/// templates) that is reused for all inputs processed by that thread.
+pub fn run<L, T, R>(options: &Options, load: L, render: R) -> Result<(), Error>
+where
+    L: Fn(&mut dyn BufRead) -> Result<String, Error> + Sync,
+    T: Default,
+    R: Fn(&mut T, &str, &str, &mut dyn Write) -> Result<(), Error> + Sync,
+{
+    let inputs = collect_inputs(options.input_dir)?;
+    ensure_unique(
+        inputs
+            .iter()
+            .map(|path| (module_name(path), path.display().to_string())),
+    )?;
+
+    let output_path = |path: &Path| {
+        output_path(
+            options.output_dir,
+            options.path_pattern,
+            &module_name(path),
+            options.extension,
+        )
+    };
+
+    fs::create_dir_all(options.output_dir).map_err(|e| Error::WriteOutput {
+        path: options.output_dir.to_owned(),
+        inner: e,
+    })?;
+
+    let mut cache = if options.use_cache {
+        Cache::load(options.output_dir)
+    } else {
+        Cache::default()
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()?;
+
+    let results: Vec<Result<Outcome, Error>> = pool.install(|| {
+        inputs
+            .par_iter()
+            .map_init(T::default, |state, path| {
+                let source = load(&mut &input::open(path)?[..])?;
+
+                let key = crate::cache::key(&source, &options.fingerprint);
+                if options.use_cache && cache.is_fresh(&output_path(path), &key) {
+                    return Ok(Outcome::Fresh);
+                }
+
+                let snapshot = migration::Snapshot::take(&module_name(path), &output_path(path));
+                create_parent(&output_path(path))?;
+
+                // Every worker streams directly into its own output file
+                write_output(&output_path(path), |writer| {
+                    render(state, &source, &module_name(path), writer)
+                })?;
+
+                Ok(Outcome::Rendered {
+                    key,
+                    notes: snapshot.notes(&output_path(path)),
+                })
+            })
+            .collect()
+    });
+
+    let mut failed = 0;
+    let mut notes = Vec::new();
+    let mut written = Vec::new();
+    for (path, result) in inputs.iter().zip(results) {
+        let output = output_path(path);
+
+        match result {
+            Ok(Outcome::Rendered {
+                key,
+                notes: module_notes,
+            }) => {
+                eprintln!("{} -> {}", path.display(), output.display());
+                cache.update(&output, key);
+                notes.extend(module_notes);
+                written.push(module_name(path));
+            }
+
+            Ok(Outcome::Fresh) => {
+                eprintln!("{} -> {} (up to date)", path.display(), output.display());
+                written.push(module_name(path));
+            }
+
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    if options.use_cache {
+        cache.save(options.output_dir)?;
+    }
+
+    if !notes.is_empty() {
+        migration::write(options.output_dir, &notes)?;
+    }
+
+    // Failed inputs have no file to declare
+    if options.mod_file {
+        write_mod_file(options.output_dir, &written)?;
+    }
+
+    // Outputs of failed inputs are not stale, so they are recorded as well
+    manifest::update(
+        options.output_dir,
+        inputs
+            .iter()
+            .map(|path| (output_path(path), path.display().to_string())),
+        options.prune,
+    )?;
+
+    if failed > 0 {
+        return Err(Error::Batch {
+            failed,
+            total: inputs.len(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_module_names() {
+        for (file, expected) in [
+            ("IERC20.json", "ierc20"),
+            ("UniswapV2Router02.abi", "uniswap_v2_router02"),
+            ("My-Token.json", "my_token"),
+        ] {
+            assert_eq!(module_name(Path::new(file)), expected);
+        }
+    }
+
+    #[test]
+    fn declares_only_written_modules() {
+        let dir = std::env::temp_dir().join(format!("sumi-batch-{}", std::process::id()));
+        let (input_dir, output_dir) = (dir.join("abi"), dir.join("out"));
+        fs::create_dir_all(&input_dir).unwrap();
+        fs::write(input_dir.join("good.json"), "[]").unwrap();
+        fs::write(input_dir.join("bad.json"), "[]").unwrap();
+
+        let options = Options {
+            input_dir: &input_dir,
+            output_dir: &output_dir,
+            extension: "rs",
+            path_pattern: None,
+            jobs: Some(1),
+            fingerprint: String::new(),
+            use_cache: false,
+            mod_file: true,
+            prune: false,
+        };
+
+        let result = run(
+            &options,
+            |_| Ok(String::new()),
+            |_: &mut (), _, module_name, writer| match module_name {
+                "bad" => Err(Error::Metadata("unsupported".to_owned())),
+                _ => Ok(writer.write_all(b"// module")?),
+            },
+        );
+
+        let mod_file = fs::read_to_string(output_dir.join(MOD_FILE)).unwrap();
+        let bad_exists = output_dir.join("bad.rs").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Error::Batch {
+                failed: 1,
+                total: 2
+            })
+        ));
+        assert!(mod_file.contains("pub mod good;"));
+        assert!(!mod_file.contains("pub mod bad;"));
+        assert!(!bad_exists);
+    }
+}
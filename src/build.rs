@@ -0,0 +1,87 @@
+use crate::{
+    artifact,
+    cli::Target,
+    error::Error,
+    input,
+    sol2ink::{self, CustomTemplates, Generator},
+    warning::{SkipReason, Warning},
+};
+use convert_case::{Case, Casing};
+use std::{fs, path::Path};
+
+/// Options of the wrapper generated from a build script
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub target: Target,
+
+    /// Module name is taken from the output file when empty
+    pub generator: sol2ink::Options,
+}
+
+impl Default for Options {
+    /// Same defaults as the command line has
+    fn default() -> Self {
+        Options {
+            target: Target::AstarXvm,
+            generator: sol2ink::Options {
+                evm_id: "0x0F".to_owned(),
+                para_id: 2004,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Generates the wrapper of the ABI from a build script, i.e.
+/// `sumi::build::generate("abi/erc20.json", "src/erc20.rs", Default::default())`.
+///
+/// Cargo is told to rerun the script when the ABI changes, so the wrapper follows it
+/// without being regenerated by hand. The output is only written when its contents
+/// change, so an unchanged wrapper does not trigger rebuilds. Warnings of the
+/// generation are reported as cargo warnings.
+pub fn generate(
+    abi: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    options: Options,
+) -> Result<(), Error> {
+    let (abi, output) = (abi.as_ref(), output.as_ref());
+    println!("cargo:rerun-if-changed={}", abi.display());
+
+    let mut generator_options = options.generator;
+    if generator_options.module_name.is_empty() {
+        generator_options.module_name = output
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_case(Case::Snake))
+            .unwrap_or_default();
+    }
+
+    let source = artifact::read_abi(&mut &input::open(abi)?[..])?;
+    let generator = Generator::new(&options.target, &CustomTemplates::default())?;
+    let model = generator.model(&json::parse(&source)?, &generator_options)?;
+
+    // Read-only functions are skipped by default, like on the command line
+    for warning in model.warnings() {
+        if !matches!(
+            warning,
+            Warning::SkippedFunction {
+                reason: SkipReason::ReadOnly,
+                ..
+            }
+        ) {
+            println!("cargo:warning={warning}");
+        }
+    }
+
+    let mut code = Vec::new();
+    generator.render_to(&model, &mut code)?;
+    code.push(b'\n');
+
+    if fs::read(output).ok().as_deref() != Some(&code[..]) {
+        fs::write(output, code).map_err(|e| Error::WriteOutput {
+            path: output.to_owned(),
+            inner: e,
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::error::Error;
+use hex::ToHex;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Name of the cache file that is stored next to generated outputs
+pub const CACHE_FILE: &str = ".sumi-cache";
+
+/// Maps generated output file names to the keys of inputs they were produced from
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    entries: BTreeMap<String, String>,
+}
+
+/// Computes cache key from input contents, generation options and sumi version
+pub fn key(source: &str, fingerprint: &str) -> String {
+    let mut hasher = Keccak256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update([0u8]);
+    hasher.update(fingerprint.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(source.as_bytes());
+
+    hasher.finalize().encode_hex()
+}
+
+impl Cache {
+    /// Loads cache from the directory. Missing or malformed cache is treated as empty.
+    pub fn load(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), Error> {
+        let path = dir.join(CACHE_FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| Error::WriteOutput { path, inner: e })
+    }
+
+    /// Output is fresh if it still exists and was produced from the same key
+    pub fn is_fresh(&self, output: &Path, key: &str) -> bool {
+        output.is_file()
+            && self
+                .entries
+                .get(&Self::entry_name(output))
+                .map_or(false, |cached| cached == key)
+    }
+
+    pub fn update(&mut self, output: &Path, key: String) {
+        self.entries.insert(Self::entry_name(output), key);
+    }
+
+    fn entry_name(output: &Path) -> String {
+        output
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    error::Error,
+    human,
+    sol2ink::{canonical_type, selector, signature},
+};
+use ethabi::{
+    token::{LenientTokenizer, Tokenizer},
+    ParamType, Token,
+};
+use hex::ToHex;
+use itertools::Itertools;
+use json::JsonValue;
+
+/// Reverts with a message and panics are encoded as calls of these
+const BUILTIN_ERRORS: &str = r#"[
+    {"type": "error", "name": "Error", "inputs": [{"type": "string", "name": "message"}]},
+    {"type": "error", "name": "Panic", "inputs": [{"type": "uint256", "name": "code"}]}
+]"#;
+
+/// Function or custom error of the ABI with parsed parameter types
+struct Entry {
+    name: String,
+    signature: String,
+    selector: [u8; 4],
+    inputs: Vec<(String, ParamType)>,
+    outputs: Vec<(String, ParamType)>,
+}
+
+impl Entry {
+    fn new(item: &JsonValue) -> Result<Self, Error> {
+        let signature = signature(item);
+        Ok(Entry {
+            name: item["name"].as_str().unwrap_or_default().to_owned(),
+            selector: selector(&signature),
+            signature,
+            inputs: params(&item["inputs"])?,
+            outputs: params(&item["outputs"])?,
+        })
+    }
+}
+
+fn params(params: &JsonValue) -> Result<Vec<(String, ParamType)>, Error> {
+    params
+        .members()
+        .map(|param| {
+            Ok((
+                param["name"].as_str().unwrap_or_default().to_owned(),
+                ethabi::param_type::Reader::read(&canonical_type(param))?,
+            ))
+        })
+        .collect()
+}
+
+fn entries(abi: &JsonValue, kind: &str) -> Result<Vec<Entry>, Error> {
+    abi.members()
+        .filter(|item| item["type"] == kind)
+        .map(Entry::new)
+        .collect()
+}
+
+/// Finds the function by its name or signature, overloaded functions need the signature
+fn find<'a>(functions: &'a [Entry], function: &str) -> Result<&'a Entry, Error> {
+    if function.contains('(') {
+        let declaration = json::parse(&human::parse(function)?)?;
+        let wanted = signature(&declaration[0]);
+
+        return functions
+            .iter()
+            .find(|entry| entry.signature == wanted)
+            .ok_or_else(|| Error::Calldata(format!("function `{wanted}` not found in the ABI")));
+    }
+
+    match functions
+        .iter()
+        .filter(|entry| entry.name == function)
+        .collect_vec()
+        .as_slice()
+    {
+        [entry] => Ok(*entry),
+        [] => Err(Error::Calldata(format!(
+            "function `{function}` not found in the ABI"
+        ))),
+        overloads => Err(Error::Calldata(format!(
+            "function `{function}` is overloaded, select one by its signature: {}",
+            overloads.iter().map(|entry| &entry.signature).join(", ")
+        ))),
+    }
+}
+
+/// Renders the token the way it is written in Solidity, except for strings that are quoted
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("0x{}", address.encode_hex::<String>()),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            format!("0x{}", bytes.encode_hex::<String>())
+        }
+        // Two's complement
+        Token::Int(value) if value.bit(255) => format!("-{}", !*value + 1),
+        Token::Int(value) | Token::Uint(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{value:?}"),
+        Token::FixedArray(tokens) | Token::Array(tokens) => {
+            format!("[{}]", tokens.iter().map(format_token).join(", "))
+        }
+        Token::Tuple(tokens) => format!("({})", tokens.iter().map(format_token).join(", ")),
+    }
+}
+
+/// Decodes the data and renders a line per parameter
+fn render(heading: &str, params: &[(String, ParamType)], data: &[u8]) -> Result<String, Error> {
+    let types = params.iter().map(|(_, ty)| ty.clone()).collect_vec();
+    let tokens = ethabi::decode(&types, data)?;
+
+    Ok(std::iter::once(heading.to_owned())
+        .chain(
+            params
+                .iter()
+                .zip(&tokens)
+                .enumerate()
+                .map(|(index, ((name, ty), token))| {
+                    let name = if name.is_empty() {
+                        index.to_string()
+                    } else {
+                        name.clone()
+                    };
+
+                    format!("  {name}: {ty} = {}", format_token(token))
+                }),
+        )
+        .join("\n"))
+}
+
+/// Encodes call of the function, given by its name or signature, with the arguments
+/// written the way ethers and cast take them. Returns hex encoded calldata.
+pub fn encode(abi: &JsonValue, function: &str, args: &[String]) -> Result<String, Error> {
+    let functions = entries(abi, "function")?;
+    let entry = find(&functions, function)?;
+    if args.len() != entry.inputs.len() {
+        return Err(Error::Calldata(format!(
+            "`{}` takes {} arguments, {} given",
+            entry.signature,
+            entry.inputs.len(),
+            args.len()
+        )));
+    }
+
+    let tokens = entry
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(index, ((_, ty), arg))| {
+            LenientTokenizer::tokenize(ty, arg)
+                .map_err(|e| Error::Calldata(format!("argument {index} is not a valid {ty}: {e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut calldata = entry.selector.to_vec();
+    calldata.extend(ethabi::encode(&tokens));
+    Ok(format!("0x{}", calldata.encode_hex::<String>()))
+}
+
+/// Decodes hex encoded return data of the function, or calldata and revert data
+/// matched against selectors of the functions and errors when no function is given
+pub fn decode(abi: &JsonValue, data: &str, function: Option<&str>) -> Result<String, Error> {
+    let data = hex::decode(data.trim().trim_start_matches("0x"))
+        .map_err(|e| Error::Calldata(format!("data is not valid hex: {e}")))?;
+
+    let functions = entries(abi, "function")?;
+    if let Some(function) = function {
+        let entry = find(&functions, function)?;
+        return render(
+            &format!("{} returns", entry.signature),
+            &entry.outputs,
+            &data,
+        );
+    }
+
+    if data.len() < 4 {
+        return Err(Error::Calldata(
+            "data is shorter than a selector".to_owned(),
+        ));
+    }
+
+    let errors = entries(abi, "error")?;
+    let builtin = entries(&json::parse(BUILTIN_ERRORS)?, "error")?;
+    match functions
+        .iter()
+        .chain(&errors)
+        .chain(&builtin)
+        .find(|entry| entry.selector[..] == data[..4])
+    {
+        Some(entry) => render(&entry.signature, &entry.inputs, &data[4..]),
+        None => Err(Error::Calldata(format!(
+            "no function or error of the ABI has selector 0x{}",
+            data[..4].encode_hex::<String>()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_and_decodes_calls() {
+        let abi = json::parse(
+            &human::parse(
+                "function transfer(address to, uint256 amount) returns (bool)\n\
+                 function transfer(address to, int8 delta)",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let calldata = encode(
+            &abi,
+            "transfer(address,uint256)",
+            &[
+                "0x0000000000000000000000000000000000000001".to_owned(),
+                "100".to_owned(),
+            ],
+        )
+        .unwrap();
+        assert!(calldata.starts_with("0xa9059cbb"));
+        assert_eq!(
+            decode(&abi, &calldata, None).unwrap(),
+            "transfer(address,uint256)\n  to: address = 0x0000000000000000000000000000000000000001\n  amount: uint256 = 100"
+        );
+
+        let calldata = encode(
+            &abi,
+            "transfer(address,int8)",
+            &[
+                "0x0000000000000000000000000000000000000001".to_owned(),
+                "-5".to_owned(),
+            ],
+        )
+        .unwrap();
+        assert!(decode(&abi, &calldata, None)
+            .unwrap()
+            .ends_with("delta: int8 = -5"));
+
+        assert!(matches!(
+            encode(&abi, "transfer", &[]),
+            Err(Error::Calldata(message)) if message.contains("overloaded")
+        ));
+    }
+}
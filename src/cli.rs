@@ -5,18 +5,506 @@ use std::path::PathBuf;
 pub enum Mode {
     EvmToInk,
     InkToEvm,
+    InkToInk,
+}
+
+/// Chain the generated ink! module calls EVM contracts on
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum Target {
+    /// Astar XVM chain extension
+    AstarXvm,
+
+    /// Moonbeam remote EVM call sent over XCM
+    MoonbeamXcm,
+
+    /// Runtime module building `pallet_evm::Call::call` dispatchables
+    /// instead of an ink! contract
+    FrontierRuntime,
+}
+
+/// How contract addresses are kept in the storage of the module
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum StorageLayout {
+    /// Loaded together with the rest of the storage on every call
+    Plain,
+
+    /// Wrapped in `ink_storage::Lazy` and loaded only when used
+    Lazy,
+}
+
+/// Additional artifacts generated alongside the module
+#[derive(Debug, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum Emit {
+    /// Solidity contract implementing the ABI with settable return data
+    SolidityMock,
+
+    /// Rust crate with subxt based client calling messages of the wrapper
+    SubxtClient,
+
+    /// Runtime side of the chain extension decoding calls made by the wrapper
+    ChainExtension,
+
+    /// Shell script with `cargo contract call` invocations of every message
+    CallScripts,
+
+    /// ethers-rs client of the EVM contract generated with `abigen!` from the same ABI
+    EthersClient,
+}
+
+/// Standard ink! interface implemented on top of the wrapped one
+#[derive(Debug, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum Adapter {
+    /// PSP22 trait of fungible tokens, forwarding to the ERC20 functions
+    Psp22,
+
+    /// PSP34 trait of NFTs, forwarding to the ERC721 functions
+    Psp34,
+}
+
+/// Format of the selector map
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum MapFormat {
+    Json,
+    Csv,
+}
+
+/// Generation and utilities working with the inputs
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Generate ink! module or Solidity wrapper, the same as running without a subcommand
+    Generate(Box<Args>),
+
+    /// Print plain ABI array of any supported input: bare ABI, Hardhat,
+    /// Foundry or Truffle artifact, solc metadata or combined JSON
+    Abi {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several, i.e. `Token` or `src/Token.sol:Token`
+        #[arg(long)]
+        contract: Option<String>,
+    },
+
+    /// Print table of function signatures and their 4-byte selectors,
+    /// followed by custom errors
+    Selectors {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+    },
+
+    /// Build calldata of a function call for debugging, i.e. `encode -i IERC20.abi transfer 0x... 100`
+    Encode {
+        /// Input filename or stdin if empty, not read when the function is given by its signature
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+
+        /// Function name or signature, i.e. `transfer` or `transfer(address,uint256)`
+        function: String,
+
+        /// Arguments of the function, arrays as `[1,2]` and tuples as `(0x...,1)`
+        #[arg(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Decode calldata or revert data against the ABI, or return data of the --function
+    Decode {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+
+        /// Decode the data as return data of the function, given by its name or signature
+        #[arg(long)]
+        function: Option<String>,
+
+        /// Hex encoded data
+        data: String,
+    },
+
+    /// Print table of functions and events of any supported input,
+    /// showing which functions the wrapper will expose
+    List {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+
+        /// Mark functions matching none of the comma separated glob patterns as not wrapped
+        #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+        only: Vec<String>,
+
+        /// Mark functions matching any of the comma separated glob patterns as not wrapped
+        #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+        exclude: Vec<String>,
+    },
+
+    /// Summarize any supported input: its format, contract, functions by
+    /// mutability, events, errors, unsupported types and compatible targets
+    Inspect {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+    },
+
+    /// Report ABI patterns producing broken or surprising wrappers: duplicates,
+    /// unnamed parameters, unusual mutability, too many arguments and
+    /// selectors of transparent proxy admin functions
+    Lint {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+    },
+
+    /// Check that the module was generated from the current revision of the ABI
+    Check {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+
+        /// Generated module to check
+        #[arg(long, short)]
+        module: PathBuf,
+    },
+
+    /// Rewrite ABI in the canonical form: sorted entries and keys, no null
+    /// fields, so ABI files kept in repositories produce minimal diffs
+    FmtAbi {
+        /// Input filename or stdin if empty
+        #[arg(long, short)]
+        input: Option<PathBuf>,
+
+        /// Output filename or stdout if empty, may be the same as the input
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Contract to take ABI of when the input contains several
+        #[arg(long)]
+        contract: Option<String>,
+    },
+
+    /// Check that the project and installed cargo-contract are
+    /// compatible with the code generated for the target
+    Doctor {
+        /// Manifest of the project the generated code is built in
+        #[arg(long, default_value = "Cargo.toml")]
+        manifest: PathBuf,
+
+        #[arg(long, short, default_value = "evm-to-ink")]
+        mode: Mode,
+
+        /// Chain to call EVM contracts on
+        #[arg(long, default_value = "astar-xvm")]
+        target: Target,
+
+        /// Shape of `xvm_call` of the astar-xvm target
+        #[arg(long, default_value = "chain-extension")]
+        xvm_version: crate::sol2ink::XvmVersion,
+    },
+
+    /// Export mapping of selectors and signatures of the functions to the
+    /// generated messages, for monitoring and block explorer tooling
+    SelectorMap {
+        /// Input filename, may be repeated
+        #[arg(long = "input", short, value_name = "FILE")]
+        inputs: Vec<PathBuf>,
+
+        /// Directory of input files to map all of
+        #[arg(long)]
+        input_dir: Option<PathBuf>,
+
+        /// Output filename or stdout if empty
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        #[arg(long, default_value = "json")]
+        format: MapFormat,
+
+        /// Mark functions matching none of the comma separated glob patterns as not wrapped
+        #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+        only: Vec<String>,
+
+        /// Mark functions matching any of the comma separated glob patterns as not wrapped
+        #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+        exclude: Vec<String>,
+
+        /// Map `view` and `pure` functions to messages as well
+        #[arg(long)]
+        include_views: bool,
+    },
+
+    /// Create a starter project: sumi.toml, abis/ directory and a script
+    /// generating a `cargo contract` project for every ABI
+    Init {
+        /// Directory to create the project in
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub args: Args,
+}
+
+/// Arguments of module generation, taken by `generate` and by the top level command
+#[derive(clap::Args, Debug, Clone)]
 pub struct Args {
     /// Input filename or stdin if empty
     #[arg(long, short)]
     pub input: Option<PathBuf>,
 
+    /// Human-readable declaration to wrap instead of an input, i.e.
+    /// `function transfer(address to, uint256 amount) returns (bool)`
+    #[arg(long = "sig", value_name = "SIGNATURE", conflicts_with_all = ["input", "input_dir"])]
+    pub signatures: Vec<String>,
+
+    /// Contract to wrap when the input contains several, i.e. `Token` or `src/Token.sol:Token`
+    #[arg(long, conflicts_with_all = ["input_dir", "fetch", "signatures"])]
+    pub contract: Option<String>,
+
     /// Output filename or stdout if empty
     #[arg(long, short)]
     pub output: Option<PathBuf>,
 
+    /// Only insert functions missing from the existing output, leaving the rest of it untouched
+    #[arg(long, requires = "output", conflicts_with_all = ["input_dir", "router"])]
+    pub append: bool,
+
+    /// Regenerate the output in memory and fail with a diff if the file differs, leaving it untouched
+    #[arg(long, requires = "output", conflicts_with_all = ["append", "emit", "output_dir"])]
+    pub check: bool,
+
+    /// Directory of input files, or a glob pattern matching them, to process in batch mode
+    #[arg(long, conflicts_with = "input")]
+    pub input_dir: Option<PathBuf>,
+
+    /// Directory to write generated modules to in batch mode
+    #[arg(long, conflicts_with = "output")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Number of worker threads in batch mode [default: number of CPUs]
+    #[arg(long, short)]
+    pub jobs: Option<usize>,
+
+    /// Regenerate all modules in batch mode, even if inputs were not changed
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Delete modules generated into --output-dir from inputs that no longer exist
+    #[arg(long, requires = "output_dir")]
+    pub prune: bool,
+
+    /// Fetch verified ABI of the contract instead of reading the input, may be repeated
+    #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["input", "input_dir"])]
+    pub fetch: Vec<String>,
+
+    /// Etherscan compatible explorer API to fetch ABIs from, i.e. Blockscout
+    #[arg(long, value_name = "URL")]
+    pub explorer_url: Option<String>,
+
+    /// API key of the explorer
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    /// Fetch ABIs from Sourcify repository of the chain instead of an explorer
+    #[arg(long, value_name = "CHAIN_ID", conflicts_with = "explorer_url")]
+    pub sourcify: Option<u64>,
+
+    /// Maximum number of fetch requests per second
+    #[arg(long, default_value_t = 5.0)]
+    pub rate_limit: f64,
+
+    /// Number of retries of a failed fetch request
+    #[arg(long, default_value_t = 3)]
+    pub retries: u32,
+
+    /// Directory to cache fetched ABIs in
+    #[arg(long, default_value = ".sumi-fetch-cache")]
+    pub fetch_cache: PathBuf,
+
+    /// Ethereum JSON-RPC endpoint to read bytecode of the --address contract from
+    #[arg(long, value_name = "URL", requires = "address", conflicts_with_all = ["input", "input_dir", "fetch"])]
+    pub rpc: Option<String>,
+
+    /// Deployed contract to identify by its bytecode
+    #[arg(long, value_name = "ADDRESS", requires = "rpc")]
+    pub address: Option<String>,
+
+    /// Directory of build artifacts to match the bytecode against before asking IPFS
+    #[arg(long, value_name = "DIR", requires = "rpc")]
+    pub registry: Option<PathBuf>,
+
+    /// IPFS gateway to download metadata of contracts missing from the registry from
+    #[arg(long, value_name = "URL", default_value = crate::rpc::IPFS_GATEWAY)]
+    pub ipfs_gateway: String,
+
+    /// Generate only functions matching any of the comma separated glob patterns
+    #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+    pub only: Vec<String>,
+
+    /// Skip functions matching any of the comma separated glob patterns, even if they match --only
+    #[arg(long, value_delimiter = ',', value_name = "PATTERNS")]
+    pub exclude: Vec<String>,
+
+    /// Config file [default: sumi.toml if exists]
+    #[arg(long, short, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Hardhat or Foundry gas report to take gas estimates of functions from
+    #[arg(long, value_name = "FILE")]
+    pub gas_report: Option<PathBuf>,
+
+    /// TOML or YAML file with doc text and examples of the messages, keyed by function name
+    #[arg(long, value_name = "FILE")]
+    pub docs: Option<PathBuf>,
+
+    /// Additional path to import in the generated module, may be repeated
+    #[arg(long, value_name = "PATH")]
+    pub extra_use: Vec<String>,
+
+    /// TinyTemplate file rendering the whole module from the model instead of the built-in sections
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["append", "router"])]
+    pub template: Option<PathBuf>,
+
+    /// Directory of templates replacing built-in sections of the same name, i.e. `message.txt`,
+    /// and partials the --template calls
+    #[arg(long, value_name = "DIR")]
+    pub template_dir: Option<PathBuf>,
+
+    /// Do not import anything the default templates rely on
+    #[arg(long)]
+    pub no_default_imports: bool,
+
+    /// Additional artifacts to generate, comma separated
+    #[arg(long, value_delimiter = ',')]
+    pub emit: Vec<Emit>,
+
+    /// Directory to write additional artifacts to [default: directory of the output]
+    #[arg(long)]
+    pub emit_dir: Option<PathBuf>,
+
+    /// Write the module as `lib.rs` of a `cargo contract` crate in the directory, creating
+    /// its `Cargo.toml` with the dependencies and `.gitignore` unless they exist
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["output", "output_dir", "input_dir", "router", "emit_deps", "write_deps"])]
+    pub scaffold: Option<PathBuf>,
+
+    /// Write every module of --input-dir as a `cargo contract` crate of the Cargo workspace
+    /// in the directory, importing the EVM types from a crate of the workspace shared by all
+    #[arg(long, value_name = "DIR", requires = "input_dir", conflicts_with_all = ["output", "output_dir", "router", "scaffold", "emit_deps", "write_deps"])]
+    pub workspace: Option<PathBuf>,
+
+    /// Print `[dependencies]` of the generated code for the selected mode and target
+    #[arg(long)]
+    pub emit_deps: bool,
+
+    /// Add dependencies of the generated code missing from the manifest, i.e. `Cargo.toml`
+    #[arg(long, value_name = "MANIFEST", conflicts_with = "emit_deps")]
+    pub write_deps: Option<PathBuf>,
+
+    /// Version requirement of the ink! crates, i.e. `=3.0.1`; its major version (3, 4 or 5) selects the generated code
+    #[arg(long, value_name = "VERSION")]
+    pub ink_version: Option<String>,
+
+    /// Version requirement of ethabi for --emit-deps and --write-deps
+    #[arg(long, value_name = "VERSION")]
+    pub ethabi_version: Option<String>,
+
+    /// Substrate and Frontier branch of the frontier-runtime dependencies, i.e. `polkadot-v0.9.37`
+    #[arg(long, value_name = "BRANCH")]
+    pub substrate_branch: Option<String>,
+
+    /// Fail when any function of the ABI is not wrapped, listing every one with the reason
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Generate off-chain unit tests of the messages calling mocked XVM chain extension
+    #[arg(long, conflicts_with_all = ["append", "router"])]
+    pub tests: bool,
+
+    /// Generate `#[ink_e2e::test]` skeletons instantiating the module on a local node
+    /// and calling every message, requires --ink-version 4 or 5
+    #[arg(long, conflicts_with_all = ["append", "router"])]
+    pub with_e2e_tests: bool,
+
+    /// Keep selectors in a single table indexed by an enum instead of
+    /// a constant per function, reducing the code of very large ABIs
+    #[arg(long, conflicts_with_all = ["append", "router"])]
+    pub selector_table: bool,
+
+    /// Also wrap view and pure functions as read-only messages returning the decoded value
+    #[arg(long)]
+    pub include_views: bool,
+
+    /// Generate a stub with the constructor and a raw call message when no functions are left to wrap
+    #[arg(long)]
+    pub allow_empty: bool,
+
+    /// Generate EIP-191 personal message digest and signer recovery helpers
+    #[arg(long)]
+    pub signature_helpers: bool,
+
+    /// Take `AccountId`, `Balance` and byte arrays in messages instead of the EVM types
+    #[arg(long)]
+    pub native_types: bool,
+
+    /// How messages of overloaded functions are named [default: enum, or the config one]
+    #[arg(long)]
+    pub overloads: Option<crate::sol2ink::Overloads>,
+
+    /// Generate `#[ink::trait_definition]` of the interface, i.e. `Erc20Like`, implemented by the messages
+    #[arg(long, conflicts_with_all = ["append", "router"])]
+    pub trait_definition: bool,
+
+    /// Return `Result<_, XvmCallError>` from messages instead of `bool` and `Option`, telling why the call failed
+    #[arg(long, conflicts_with = "append")]
+    pub typed_errors: bool,
+
+    /// Implement a standard ink! trait by calling the EVM contract, i.e. PSP22 for ERC20 tokens
+    #[arg(long, conflicts_with_all = ["append", "router"])]
+    pub adapter: Option<Adapter>,
+
+    /// Generate single router contract with the given name, wrapping
+    /// every contract of --input-dir or --fetch under its own namespace
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["output_dir", "emit", "module_name"])]
+    pub router: Option<String>,
+
     /// Ink module name to generate
     #[arg(long)]
     pub module_name: Option<String>,
@@ -25,6 +513,26 @@ pub struct Args {
     #[arg(long, short, default_value = "0x0F")]
     pub evm_id: String,
 
+    /// Chain to call EVM contracts on
+    #[arg(long, default_value = "astar-xvm")]
+    pub target: Target,
+
+    /// Shape of `xvm_call` of the astar-xvm target, which differs between Astar runtime versions
+    #[arg(long, default_value = "chain-extension")]
+    pub xvm_version: crate::sol2ink::XvmVersion,
+
+    /// Keep the gas limit of the calls in storage with a setter for the instantiating account, for --xvm-version v3
+    #[arg(long, conflicts_with = "router")]
+    pub gas_limit_setter: bool,
+
+    /// How contract addresses are kept in the storage
+    #[arg(long, default_value = "plain")]
+    pub storage: StorageLayout,
+
+    /// Parachain ID of Moonbeam for the moonbeam-xcm target
+    #[arg(long, default_value_t = 2004)]
+    pub para_id: u32,
+
     #[arg(long, short, default_value = "evm-to-ink")]
     pub mode: Mode,
 }
@@ -0,0 +1,46 @@
+use crate::{error::Error, sol2ink::Model};
+use convert_case::{Case, Casing};
+use serde::Serialize;
+use std::path::PathBuf;
+use tinytemplate::{format_unescaped, TinyTemplate};
+
+static TEMPLATES: &[(&str, &str)] = &[
+    ("manifest", include_str!("../templates/client-manifest.txt")),
+    ("lib", include_str!("../templates/client-lib.txt")),
+];
+
+#[derive(Serialize)]
+struct Context<'a> {
+    crate_name: String,
+    client_name: String,
+    model: &'a Model,
+}
+
+/// Name of the client crate directory of the module
+pub fn crate_name(module_name: &str) -> String {
+    format!("{}_client", module_name.to_case(Case::Snake))
+}
+
+/// Renders subxt based client crate of the wrapper as a list of files
+/// relative to the crate directory
+pub fn render(model: &Model, module_name: &str) -> Result<Vec<(PathBuf, String)>, Error> {
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&format_unescaped);
+    for &(name, text) in TEMPLATES {
+        template.add_template(name, text)?;
+    }
+
+    let context = Context {
+        crate_name: crate_name(module_name),
+        client_name: format!("{}Client", module_name.to_case(Case::UpperCamel)),
+        model,
+    };
+
+    Ok(vec![
+        ("Cargo.toml".into(), template.render("manifest", &context)?),
+        (
+            PathBuf::from("src").join("lib.rs"),
+            template.render("lib", &context)?,
+        ),
+    ])
+}
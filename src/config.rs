@@ -0,0 +1,409 @@
+use crate::{
+    error::Error,
+    filter::Filter,
+    sol2ink::{self, TargetGroup},
+};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Config file picked up from the working directory when `--config` is not given
+pub const DEFAULT_CONFIG: &str = "sumi.toml";
+
+/// Generation settings that are too detailed for the command line
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Cargo features gating functions matching the glob patterns, i.e.
+    /// `admin = ["set*", "pause"]`
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+
+    /// Groups of functions calling other contracts than the module
+    /// address, each stored in the `<group>_evm_address` field
+    #[serde(default)]
+    pub targets: BTreeMap<String, Target>,
+
+    #[serde(default)]
+    pub imports: Imports,
+
+    #[serde(default)]
+    pub module: Module,
+
+    #[serde(default)]
+    pub storage: Storage,
+
+    /// Address argument filled with the EVM address of the message caller
+    pub caller: Option<Caller>,
+
+    #[serde(default)]
+    pub accounts: Accounts,
+
+    #[serde(default)]
+    pub abi: Abi,
+
+    /// Value, gas and argument types of the calls, keyed by function name
+    #[serde(default)]
+    pub functions: BTreeMap<String, Function>,
+
+    /// Rust types replacing the default mapping of the EVM types, i.e. `uint256 = "u128"`
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub output: Output,
+
+    /// Functions to generate, see [`Config::filter`]
+    #[serde(default)]
+    pub select: Select,
+
+    /// Shared templates, see [`TemplatePack`]
+    pub templates: Option<TemplatePack>,
+}
+
+/// Templates shared across repositories: `github:<org>/<repo>#<ref>`,
+/// `git+<url>#<ref>` or a local directory, optionally pinned to the
+/// integrity of its templates, i.e. `{ source = "...", integrity = "keccak256:..." }`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TemplatePack {
+    Source(String),
+    Pinned { source: String, integrity: String },
+}
+
+impl TemplatePack {
+    pub fn source(&self) -> &str {
+        match self {
+            TemplatePack::Source(source) | TemplatePack::Pinned { source, .. } => source,
+        }
+    }
+
+    pub fn integrity(&self) -> Option<&str> {
+        match self {
+            TemplatePack::Source(_) => None,
+            TemplatePack::Pinned { integrity, .. } => Some(integrity),
+        }
+    }
+}
+
+/// Glob patterns of the functions to generate, i.e. `only = ["transfer*", "approve"]`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Select {
+    #[serde(default)]
+    pub only: Vec<String>,
+
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Placement of generated files in batch mode
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Output {
+    /// Path of every module relative to the output directory, where `{name}`
+    /// is replaced by the module name, i.e. `crates/{name}/src/lib.rs`
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Function {
+    /// Value in wei, i.e. `"1_000_000"`
+    #[serde(default, deserialize_with = "amount")]
+    pub default_value: Option<u128>,
+
+    pub default_gas: Option<u64>,
+
+    /// Rust types of the arguments, keyed by the parameter name of the ABI,
+    /// taking precedence over the `[types]` mapping
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+}
+
+/// Amount written as an integer or as a string, which may
+/// exceed the TOML integer range and contain `_` separators
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Amount {
+    Number(u64),
+    Text(String),
+}
+
+fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u128>, D::Error> {
+    match Amount::deserialize(deserializer)? {
+        Amount::Number(number) => Ok(Some(number.into())),
+        Amount::Text(text) => text
+            .replace('_', "")
+            .parse()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("invalid amount `{text}`"))),
+    }
+}
+
+/// Handling of unusual ABIs
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Abi {
+    /// How functions without `stateMutability` and legacy `constant` fields are treated
+    #[serde(default)]
+    pub missing_mutability: sol2ink::MissingMutability,
+
+    /// How messages of overloaded functions are named
+    #[serde(default)]
+    pub overloads: sol2ink::Overloads,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Caller {
+    /// Name of the `address` argument, i.e. `from`
+    pub argument: String,
+
+    /// Glob patterns of the functions [default: all having the argument]
+    #[serde(default)]
+    pub functions: Vec<String>,
+
+    #[serde(default)]
+    pub mapping: AccountMapping,
+}
+
+/// Accounts messages take with `--native-types`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Accounts {
+    #[serde(default)]
+    pub mapping: AccountMapping,
+}
+
+/// How EVM address is derived from the account of the caller
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountMapping {
+    /// First 20 bytes of the account
+    #[default]
+    Truncate,
+
+    /// Last 20 bytes of Keccak256 hash of the account
+    Keccak,
+}
+
+/// Additions to the `#[ink(storage)]` struct, needed when
+/// the wrapper is embedded into a larger contract
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Storage {
+    /// Additional derives, i.e. `Default`
+    #[serde(default)]
+    pub derives: Vec<String>,
+
+    /// Additional attributes, i.e. `cfg_attr(feature = "std", derive(Debug))`
+    #[serde(default)]
+    pub attributes: Vec<String>,
+}
+
+/// Settings of the generated module as a whole
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Module {
+    /// Inner attributes of the module, i.e. `allow(clippy::too_many_arguments)`
+    #[serde(default)]
+    pub attributes: Vec<String>,
+}
+
+/// `use` statements of the generated module
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Imports {
+    /// Whether to keep the imports of the default templates
+    #[serde(default = "default_imports")]
+    pub default: bool,
+
+    /// Additional paths to import, i.e. `ink_prelude::collections::BTreeMap`
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+impl Default for Imports {
+    fn default() -> Self {
+        Imports {
+            default: default_imports(),
+            extra: Vec::new(),
+        }
+    }
+}
+
+fn default_imports() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Target {
+    /// Glob patterns of the group functions
+    pub functions: Vec<String>,
+
+    /// EVM ID of the group contract [default: module EVM ID]
+    pub evm_id: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+            path: path.to_owned(),
+            inner: e,
+        })?;
+
+        let config: Config =
+            toml::from_str(&text).map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+
+        if let Some(ty) = config
+            .types
+            .keys()
+            .find(|ty| ethabi::param_type::Reader::read(ty).is_err())
+        {
+            return Err(Error::Config(format!(
+                "{}: `{ty}` of [types] is not an EVM type",
+                path.display()
+            )));
+        }
+
+        // Without the name every module would be written to the same file
+        match &config.output.path {
+            Some(pattern) if !pattern.contains("{name}") || Path::new(pattern).is_absolute() => {
+                Err(Error::Config(format!(
+                    "{}: output path `{pattern}` should be relative and contain `{{name}}`",
+                    path.display()
+                )))
+            }
+            _ => Ok(config),
+        }
+    }
+
+    /// Loads the config file if given, or the default one if it exists
+    pub fn discover(path: Option<&Path>) -> Result<Self, Error> {
+        match path {
+            Some(path) => Self::load(path),
+            None if Path::new(DEFAULT_CONFIG).is_file() => Self::load(Path::new(DEFAULT_CONFIG)),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Target groups in the order functions are matched against them
+    pub fn target_groups(&self) -> Vec<TargetGroup> {
+        self.targets
+            .iter()
+            .filter(|(_, target)| !target.functions.is_empty())
+            .map(|(name, target)| TargetGroup {
+                name: name.clone(),
+                filter: Filter::new(target.functions.clone()),
+                evm_id: target.evm_id.clone(),
+            })
+            .collect()
+    }
+
+    /// Contents of the module attributes, which may also be written in full: `#![...]`
+    pub fn module_attributes(&self) -> Vec<String> {
+        contents(&self.module.attributes, "#![")
+    }
+
+    /// Storage struct additions, attributes may also be written in full: `#[...]`
+    pub fn storage(&self, lazy: bool) -> sol2ink::Storage {
+        sol2ink::Storage {
+            derives: self.storage.derives.clone(),
+            attributes: contents(&self.storage.attributes, "#["),
+            lazy,
+        }
+    }
+
+    /// Caller forwarding along with the filter of its functions
+    pub fn caller(&self) -> Option<sol2ink::CallerForwarding> {
+        self.caller
+            .as_ref()
+            .map(|caller| sol2ink::CallerForwarding {
+                argument: caller.argument.clone(),
+                filter: Filter::new(caller.functions.clone()),
+                mapping: sol2ink::CallerAddress {
+                    hashed: matches!(caller.mapping, AccountMapping::Keccak),
+                },
+            })
+    }
+
+    /// How messages taking `AccountId` derive the EVM address from it
+    pub fn account_mapping(&self) -> sol2ink::CallerAddress {
+        sol2ink::CallerAddress {
+            hashed: matches!(self.accounts.mapping, AccountMapping::Keccak),
+        }
+    }
+
+    /// Type mapping overrides, accounts are derived the same way `--native-types` does
+    pub fn type_overrides(&self) -> sol2ink::TypeOverrides {
+        sol2ink::TypeOverrides {
+            types: self
+                .types
+                .iter()
+                .filter_map(|(ty, rust_type)| {
+                    let ty = ethabi::param_type::Reader::read(ty).ok()?;
+                    Some((ty, rust_type.clone()))
+                })
+                .collect(),
+            params: self
+                .functions
+                .iter()
+                .filter(|(_, function)| !function.types.is_empty())
+                .map(|(name, function)| (name.clone(), function.types.clone()))
+                .collect(),
+            account_mapping: self.account_mapping(),
+        }
+    }
+
+    /// Filter of the functions to generate: `only` patterns of the command line
+    /// replace the configured ones, while `exclude` patterns of both apply
+    pub fn filter(&self, only: &[String], exclude: &[String]) -> Filter {
+        let only = if only.is_empty() {
+            &self.select.only
+        } else {
+            only
+        };
+
+        Filter::new(only.to_vec())
+            .excluding(self.select.exclude.iter().chain(exclude).cloned().collect())
+    }
+
+    /// Value and gas defaults of the functions having any
+    pub fn call_defaults(&self) -> BTreeMap<String, sol2ink::CallDefaults> {
+        self.functions
+            .iter()
+            .map(|(name, function)| {
+                let defaults = sol2ink::CallDefaults {
+                    value: function.default_value,
+                    gas: function.default_gas,
+                };
+                (name.clone(), defaults)
+            })
+            .collect()
+    }
+
+    /// Feature names along with filters selecting their functions
+    pub fn feature_filters(&self) -> Vec<(String, Filter)> {
+        self.features
+            .iter()
+            .filter(|(_, patterns)| !patterns.is_empty())
+            .map(|(feature, patterns)| (feature.clone(), Filter::new(patterns.clone())))
+            .collect()
+    }
+}
+
+/// Strips the attribute syntax around contents of the attributes, if present
+fn contents(attributes: &[String], open: &str) -> Vec<String> {
+    attributes
+        .iter()
+        .map(|attribute| {
+            let attribute = attribute.trim();
+            attribute
+                .strip_prefix(open)
+                .and_then(|inner| inner.strip_suffix(']'))
+                .unwrap_or(attribute)
+                .to_owned()
+        })
+        .collect()
+}
@@ -0,0 +1,392 @@
+use crate::{
+    cli::{Mode, Target},
+    doctor::{major_version, INK_MAJOR},
+    error::Error,
+    sol2ink::{InkCrates, XvmVersion},
+};
+use std::{fs, path::Path};
+use toml_edit::{Array, Document, Item, Value};
+
+/// Group of crates whose version could be pinned from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pin {
+    Ink,
+    Ethabi,
+
+    /// Substrate and Frontier crates taken from the same branch
+    Substrate,
+}
+
+/// Crate the generated code depends on
+pub struct Dependency {
+    pub name: &'static str,
+    pub spec: &'static str,
+
+    /// Whether the crate has `std` feature that has to be enabled by the `std` feature of the contract
+    pub std: bool,
+    pub pin: Option<Pin>,
+}
+
+const fn dependency(name: &'static str, spec: &'static str, std: bool) -> Dependency {
+    Dependency {
+        name,
+        spec,
+        std,
+        pin: None,
+    }
+}
+
+const fn pinned(name: &'static str, spec: &'static str, pin: Pin) -> Dependency {
+    Dependency {
+        name,
+        spec,
+        std: true,
+        pin: Some(pin),
+    }
+}
+
+/// Versions replacing the default requirements of the dependencies,
+/// for projects stuck on older releases
+#[derive(Debug, Default, Clone)]
+pub struct Versions {
+    /// Requirement of the ink! crates, i.e. `=3.0.1`
+    pub ink: Option<String>,
+    pub ethabi: Option<String>,
+
+    /// Branch of Substrate and Frontier, i.e. `polkadot-v0.9.37`
+    pub substrate_branch: Option<String>,
+}
+
+impl Versions {
+    /// Templates are written for ink! 3, 4 and 5
+    pub fn validate(&self) -> Result<(), Error> {
+        match &self.ink {
+            Some(ink) if self.ink_crates().is_none() => Err(Error::Manifest(format!(
+                "ink! {ink} is not supported, generated code needs ink! 3, 4 or 5"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Major version of ink! the code is generated for, [`INK_MAJOR`] unless pinned
+    pub fn ink_major(&self) -> Option<u64> {
+        match &self.ink {
+            Some(ink) => major_version(ink),
+            None => Some(INK_MAJOR),
+        }
+    }
+
+    /// Paths of the ink! crates the code is generated with, `None` for unsupported versions
+    pub fn ink_crates(&self) -> Option<InkCrates> {
+        self.ink_major().and_then(InkCrates::of)
+    }
+
+    /// Spec of the dependency with the pinned version or branch
+    pub fn spec(&self, dependency: &Dependency) -> Result<String, Error> {
+        let (key, pinned) = match dependency.pin {
+            Some(Pin::Ink) => ("version", &self.ink),
+            Some(Pin::Ethabi) => ("version", &self.ethabi),
+            Some(Pin::Substrate) => ("branch", &self.substrate_branch),
+            None => return Ok(dependency.spec.to_owned()),
+        };
+
+        let pinned = match pinned {
+            Some(pinned) => pinned,
+            None => return Ok(dependency.spec.to_owned()),
+        };
+
+        let invalid = |e: &dyn std::fmt::Display| {
+            Error::Manifest(format!("spec of {}: {e}", dependency.name))
+        };
+
+        let mut spec = dependency.spec.parse::<Value>().map_err(|e| invalid(&e))?;
+        spec.as_inline_table_mut()
+            .ok_or_else(|| invalid(&"not a table"))?
+            .insert(key, Value::from(pinned.as_str()));
+
+        Ok(spec.to_string())
+    }
+}
+
+/// Crates of ink! 3, generated code uses the separate ones
+const INK: &[Dependency] = &[
+    pinned(
+        "ink_env",
+        r#"{ version = "3.4", default-features = false }"#,
+        Pin::Ink,
+    ),
+    pinned(
+        "ink_lang",
+        r#"{ version = "3.4", default-features = false }"#,
+        Pin::Ink,
+    ),
+    pinned(
+        "ink_prelude",
+        r#"{ version = "3.4", default-features = false }"#,
+        Pin::Ink,
+    ),
+    pinned(
+        "ink_storage",
+        r#"{ version = "3.4", default-features = false }"#,
+        Pin::Ink,
+    ),
+];
+
+/// ink! 4 merged its crates into `ink`, the requirement of ink! 5 comes from `--ink-version`
+const INK_MERGED: &[Dependency] = &[pinned(
+    "ink",
+    r#"{ version = "4", default-features = false }"#,
+    Pin::Ink,
+)];
+
+const SCALE: &[Dependency] = &[
+    dependency(
+        "scale",
+        r#"{ package = "parity-scale-codec", version = "3", default-features = false, features = ["derive"] }"#,
+        true,
+    ),
+    dependency(
+        "scale-info",
+        r#"{ version = "2", default-features = false, features = ["derive"] }"#,
+        true,
+    ),
+];
+
+const EVM: &[Dependency] = &[
+    pinned(
+        "ethabi",
+        r#"{ version = "18", default-features = false }"#,
+        Pin::Ethabi,
+    ),
+    dependency("hex-literal", r#""0.3""#, false),
+];
+
+const XVM: &[Dependency] = &[dependency(
+    "xvm_environment",
+    r#"{ git = "https://github.com/AstarNetwork/ink-xvm-sdk", default-features = false }"#,
+    true,
+)];
+
+/// Substrate crates have to come from the same branch as the rest of the runtime
+const RUNTIME: &[Dependency] = &[
+    dependency(
+        "codec",
+        r#"{ package = "parity-scale-codec", version = "3", default-features = false, features = ["derive"] }"#,
+        true,
+    ),
+    dependency(
+        "scale-info",
+        r#"{ version = "2", default-features = false, features = ["derive"] }"#,
+        true,
+    ),
+    pinned(
+        "sp-core",
+        r#"{ git = "https://github.com/paritytech/substrate", branch = "polkadot-v0.9.39", default-features = false }"#,
+        Pin::Substrate,
+    ),
+    pinned(
+        "sp-io",
+        r#"{ git = "https://github.com/paritytech/substrate", branch = "polkadot-v0.9.39", default-features = false }"#,
+        Pin::Substrate,
+    ),
+    pinned(
+        "sp-std",
+        r#"{ git = "https://github.com/paritytech/substrate", branch = "polkadot-v0.9.39", default-features = false }"#,
+        Pin::Substrate,
+    ),
+    pinned(
+        "pallet-evm",
+        r#"{ git = "https://github.com/paritytech/frontier", branch = "polkadot-v0.9.39", default-features = false }"#,
+        Pin::Substrate,
+    ),
+];
+
+/// Crate of the workspace defining the EVM types, next to the contract crates
+pub const TYPES: Dependency = dependency(
+    "evm_types",
+    r#"{ path = "../evm_types", default-features = false }"#,
+    true,
+);
+
+/// Crates of the ink! version pinned by the versions
+fn ink_group(versions: &Versions) -> &'static [Dependency] {
+    match versions.ink_crates() {
+        Some(crates) if !crates.separate => INK_MERGED,
+        _ => INK,
+    }
+}
+
+/// Dependencies of the code generated in the mode for the target. Modules calling
+/// `pallet-xvm` declare the chain extension themselves, so only the chain extension
+/// version of XVM needs its crate.
+pub fn of(
+    mode: &Mode,
+    target: &Target,
+    xvm_version: XvmVersion,
+    versions: &Versions,
+) -> Vec<&'static Dependency> {
+    let ink = ink_group(versions);
+    let groups = match (mode, target) {
+        (Mode::EvmToInk, Target::AstarXvm) if xvm_version == XvmVersion::ChainExtension => {
+            vec![ink, SCALE, EVM, XVM]
+        }
+        (Mode::EvmToInk, Target::AstarXvm) => vec![ink, SCALE, EVM],
+        (Mode::EvmToInk, Target::MoonbeamXcm) => vec![ink, SCALE, EVM],
+        (Mode::EvmToInk, Target::FrontierRuntime) => vec![RUNTIME, EVM],
+        (Mode::InkToInk, _) => vec![ink, SCALE],
+
+        // Solidity code has no Cargo dependencies
+        (Mode::InkToEvm, _) => Vec::new(),
+    };
+
+    groups.into_iter().flatten().collect()
+}
+
+/// Dependencies of the [`TYPES`] crate
+pub fn of_types(versions: &Versions) -> Vec<&'static Dependency> {
+    ink_group(versions)
+        .iter()
+        .chain(SCALE)
+        .chain(EVM.iter().filter(|dependency| dependency.name == "ethabi"))
+        .collect()
+}
+
+/// `[dependencies]` table of the manifest
+pub fn render(dependencies: &[&Dependency], versions: &Versions) -> Result<String, Error> {
+    let mut table = String::from("[dependencies]\n");
+    for dependency in dependencies {
+        table.push_str(&format!(
+            "{} = {}\n",
+            dependency.name,
+            versions.spec(dependency)?
+        ));
+    }
+
+    Ok(table)
+}
+
+/// Adds missing dependencies to the manifest along with their `std` features,
+/// keeping the rest of the file including formatting and comments intact.
+/// Dependencies already present are not changed. Returns names of the added ones.
+pub fn write(
+    path: &Path,
+    dependencies: &[&Dependency],
+    versions: &Versions,
+) -> Result<Vec<&'static str>, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+        path: path.to_owned(),
+        inner: e,
+    })?;
+
+    let invalid = |e: &dyn std::fmt::Display| Error::Manifest(format!("{}: {e}", path.display()));
+    let mut manifest = text.parse::<Document>().map_err(|e| invalid(&e))?;
+
+    let table = manifest["dependencies"]
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| invalid(&"[dependencies] is not a table"))?;
+
+    let mut added = Vec::new();
+    for dependency in dependencies {
+        if !table.contains_key(dependency.name) {
+            let spec = versions
+                .spec(dependency)?
+                .parse::<Value>()
+                .map_err(|e| invalid(&e))?;
+            table.insert(dependency.name, Item::Value(spec));
+            added.push(dependency.name);
+        }
+    }
+
+    // Contracts enable `std` of their dependencies by their own `std` feature
+    if dependencies.iter().any(|dependency| dependency.std) {
+        let std = manifest["features"]["std"]
+            .or_insert(Item::Value(Value::Array(Array::new())))
+            .as_array_mut()
+            .ok_or_else(|| invalid(&"`std` feature is not an array"))?;
+
+        for dependency in dependencies.iter().filter(|dependency| dependency.std) {
+            let feature = format!("{}/std", dependency.name);
+            if !std.iter().any(|item| item.as_str() == Some(&feature)) {
+                std.push(feature);
+            }
+        }
+    }
+
+    fs::write(path, manifest.to_string()).map_err(|e| Error::WriteOutput {
+        path: path.to_owned(),
+        inner: e,
+    })?;
+
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_versions() {
+        let versions = Versions {
+            ink: Some("=3.0.1".to_owned()),
+            substrate_branch: Some("polkadot-v0.9.37".to_owned()),
+            ..Default::default()
+        };
+
+        let xvm = XvmVersion::ChainExtension;
+        let specs = of(&Mode::EvmToInk, &Target::AstarXvm, xvm, &versions)
+            .into_iter()
+            .chain(of(
+                &Mode::EvmToInk,
+                &Target::FrontierRuntime,
+                xvm,
+                &versions,
+            ))
+            .map(|dependency| versions.spec(dependency).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(specs
+            .iter()
+            .any(|spec| spec.contains(r#"version = "=3.0.1""#)));
+        assert!(specs.contains(&r#"{ version = "18", default-features = false }"#.to_owned()));
+        assert!(specs
+            .iter()
+            .any(|spec| spec.contains(r#"branch = "polkadot-v0.9.37""#)));
+        assert!(!specs.iter().any(|spec| spec.contains("polkadot-v0.9.39")));
+
+        assert!(Versions {
+            ink: Some("6.0".to_owned()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn depends_on_merged_ink() {
+        for ink in ["4.2", "5.0"] {
+            let versions = Versions {
+                ink: Some(ink.to_owned()),
+                ..Default::default()
+            };
+            assert!(versions.validate().is_ok());
+
+            let dependencies = of(
+                &Mode::EvmToInk,
+                &Target::AstarXvm,
+                XvmVersion::ChainExtension,
+                &versions,
+            );
+            let names = dependencies
+                .iter()
+                .map(|dependency| dependency.name)
+                .collect::<Vec<_>>();
+            assert!(names.contains(&"ink"));
+            assert!(!names.contains(&"ink_lang"));
+            assert!(versions
+                .spec(dependencies[0])
+                .unwrap()
+                .contains(&format!(r#"version = "{ink}""#)));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt::Write;
+
+/// Lines of unchanged context around every change
+const CONTEXT: usize = 3;
+
+/// Changed regions above this many line pairs are not compared line by line,
+/// they are reported as removed and added as a whole
+const MAX_COMPARED: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Longest common subsequence of the lines, the rest is removed or added
+fn compare<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut lines = old[..prefix]
+        .iter()
+        .map(|&line| Line::Same(line))
+        .collect::<Vec<_>>();
+    if old_middle.len() * new_middle.len() > MAX_COMPARED {
+        lines.extend(old_middle.iter().map(|&line| Line::Removed(line)));
+        lines.extend(new_middle.iter().map(|&line| Line::Added(line)));
+    } else {
+        // Lengths of the common subsequences of the line suffixes
+        let width = new_middle.len() + 1;
+        let mut lengths = vec![0u32; (old_middle.len() + 1) * width];
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lengths[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lengths[(i + 1) * width + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_middle.len() && j < new_middle.len() {
+            if old_middle[i] == new_middle[j] {
+                lines.push(Line::Same(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+                lines.push(Line::Removed(old_middle[i]));
+                i += 1;
+            } else {
+                lines.push(Line::Added(new_middle[j]));
+                j += 1;
+            }
+        }
+
+        lines.extend(old_middle[i..].iter().map(|&line| Line::Removed(line)));
+        lines.extend(new_middle[j..].iter().map(|&line| Line::Added(line)));
+    }
+
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|&line| Line::Same(line)),
+    );
+    lines
+}
+
+/// Unified diff turning `old` into `new`, `None` when they are the same
+pub fn unified(old: &str, new: &str, old_name: &str, new_name: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let (old_lines, new_lines) = (
+        old.lines().collect::<Vec<_>>(),
+        new.lines().collect::<Vec<_>>(),
+    );
+    let lines = compare(&old_lines, &new_lines);
+
+    let mut diff = format!("--- {old_name}\n+++ {new_name}\n");
+    let changes = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Same(_)))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    // Only the trailing newline differs
+    if changes.is_empty() {
+        diff.push_str("\\ trailing newline differs\n");
+        return Some(diff);
+    }
+
+    let mut start = 0;
+    while start < changes.len() {
+        // Changes closer than twice the context share a hunk
+        let mut end = start;
+        while end + 1 < changes.len() && changes[end + 1] - changes[end] <= 2 * CONTEXT + 1 {
+            end += 1;
+        }
+
+        let first = changes[start].saturating_sub(CONTEXT);
+        let last = (changes[end] + CONTEXT + 1).min(lines.len());
+
+        let counts = |lines: &[Line], removed: bool| {
+            lines
+                .iter()
+                .filter(|line| match line {
+                    Line::Same(_) => true,
+                    Line::Removed(_) => removed,
+                    Line::Added(_) => !removed,
+                })
+                .count()
+        };
+
+        let old_start = counts(&lines[..first], true) + 1;
+        let new_start = counts(&lines[..first], false) + 1;
+        let _ = writeln!(
+            diff,
+            "@@ -{old_start},{} +{new_start},{} @@",
+            counts(&lines[first..last], true),
+            counts(&lines[first..last], false)
+        );
+
+        for line in &lines[first..last] {
+            let _ = match line {
+                Line::Same(text) => writeln!(diff, " {text}"),
+                Line::Removed(text) => writeln!(diff, "-{text}"),
+                Line::Added(text) => writeln!(diff, "+{text}"),
+            };
+        }
+
+        start = end + 1;
+    }
+
+    Some(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lines() {
+        assert_eq!(unified("a\nb\n", "a\nb\n", "old", "new"), None);
+        assert_eq!(
+            unified("a\nb\nc\n", "a\nx\nc\n", "old", "new").as_deref(),
+            Some("--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n-b\n+x\n c\n")
+        );
+    }
+}
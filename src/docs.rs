@@ -0,0 +1,84 @@
+use crate::error::Error;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Curated documentation of the generated messages, keyed by function name
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Docs {
+    functions: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    /// Text added to the doc comment of the message
+    #[serde(default)]
+    pub doc: String,
+
+    /// Code showing how the message is used
+    #[serde(default)]
+    pub example: String,
+}
+
+impl Docs {
+    /// Loads YAML file if it has `.yaml` or `.yml` extension, TOML file otherwise
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+            path: path.to_owned(),
+            inner: e,
+        })?;
+
+        let is_yaml = path
+            .extension()
+            .map_or(false, |ext| ext == "yaml" || ext == "yml");
+
+        let docs = if is_yaml {
+            serde_yaml::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&text).map_err(|e| e.to_string())
+        };
+
+        docs.map_err(|e| Error::Docs(format!("{}: {e}", path.display())))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.functions.get(name)
+    }
+}
+
+/// Lines of the text as they follow `///`, so empty lines have no trailing space
+pub fn comment_lines(text: &str) -> Vec<String> {
+    text.trim_end()
+        .lines()
+        .map(|line| match line.trim_end() {
+            "" => String::new(),
+            line => format!(" {line}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toml_and_yaml() {
+        let toml = "[transfer]\ndoc = \"Moves tokens.\\n\\nFails without balance.\"\n";
+        let docs: Docs = toml::from_str(toml).unwrap();
+        let entry = docs.get("transfer").unwrap();
+        assert_eq!(
+            comment_lines(&entry.doc),
+            [" Moves tokens.", "", " Fails without balance."]
+        );
+        assert!(entry.example.is_empty());
+
+        let yaml = "approve:\n  example: |\n    token.approve(spender, amount);\n";
+        let docs: Docs = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            comment_lines(&docs.get("approve").unwrap().example),
+            [" token.approve(spender, amount);"]
+        );
+        assert!(docs.get("transfer").is_none());
+    }
+}
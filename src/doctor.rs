@@ -0,0 +1,204 @@
+use crate::{
+    cli::{Mode, Target},
+    deps,
+    error::Error,
+    sol2ink::{InkCrates, XvmVersion},
+};
+use std::{fs, ops::RangeInclusive, path::Path, process::Command};
+
+/// Major version of ink! the code is generated for unless `--ink-version` tells otherwise
+pub const INK_MAJOR: u64 = 3;
+
+/// cargo-contract releases building contracts of the ink! version
+fn cargo_contract_majors(ink: u64) -> RangeInclusive<u64> {
+    match ink {
+        3 => 1..=1,
+        4 => 2..=3,
+        _ => 4..=4,
+    }
+}
+
+/// Result of a single check
+pub struct Check {
+    pub ok: bool,
+    pub message: String,
+}
+
+fn ok(message: String) -> Check {
+    Check { ok: true, message }
+}
+
+fn problem(message: String) -> Check {
+    Check { ok: false, message }
+}
+
+/// Major version of a version requirement, i.e. `3` of `^3.4.0` or `=3.4`
+pub fn major_version(requirement: &str) -> Option<u64> {
+    requirement
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Version of cargo-contract from its `--version` output, i.e. `cargo-contract 1.5.0-unknown-...`
+fn cargo_contract_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|version| version.split('-').next().unwrap_or(version).to_owned())
+}
+
+fn check_cargo_contract(ink: u64) -> Check {
+    let majors = cargo_contract_majors(ink);
+    let install = majors.start();
+    let output = Command::new("cargo")
+        .args(["contract", "--version"])
+        .output();
+    let version = output
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| cargo_contract_version(&String::from_utf8_lossy(&output.stdout)));
+
+    match version {
+        Some(version) if major_version(&version).map_or(false, |major| majors.contains(&major)) => {
+            ok(format!("cargo-contract {version}"))
+        }
+        Some(version) => problem(format!(
+            "cargo-contract {version} does not build ink! {ink} contracts, \
+             install {install}.x: cargo install cargo-contract --version ^{install}"
+        )),
+        None => problem(format!(
+            "cargo-contract is not installed, install {install}.x: \
+             cargo install cargo-contract --version ^{install}"
+        )),
+    }
+}
+
+/// Version requirement of the dependency, which is either a string or a table
+fn requirement(dependency: &toml::Value) -> Option<&str> {
+    dependency
+        .as_str()
+        .or_else(|| dependency.get("version")?.as_str())
+}
+
+/// Checks of the manifest along with the major version of ink! the project uses
+fn check_manifest(
+    path: &Path,
+    mode: &Mode,
+    target: &Target,
+    xvm_version: XvmVersion,
+    ink: bool,
+) -> (Vec<Check>, u64) {
+    let manifest = match fs::read_to_string(path) {
+        Ok(text) => match text.parse::<toml::Value>() {
+            Ok(manifest) => manifest,
+            Err(e) => return (vec![problem(format!("{}: {e}", path.display()))], INK_MAJOR),
+        },
+        Err(e) => {
+            let check = problem(format!("unable to read {}: {e}", path.display()));
+            return (vec![check], INK_MAJOR);
+        }
+    };
+
+    let dependencies = manifest.get("dependencies");
+    let dependency = |name: &str| dependencies.and_then(|table| table.get(name));
+    let mut checks = Vec::new();
+    let mut ink_major = INK_MAJOR;
+
+    // ink! 4 replaced `ink_lang` and the other crates with `ink`
+    let ink_crate = ["ink", "ink_lang"]
+        .into_iter()
+        .find_map(|name| Some((name, dependency(name).and_then(requirement)?)));
+
+    if let Some((name, version)) = ink_crate.filter(|_| ink) {
+        match major_version(version).filter(|&major| InkCrates::of(major).is_some()) {
+            Some(INK_MAJOR) => checks.push(ok(format!("{name} {version}"))),
+            Some(major) => {
+                ink_major = major;
+                checks.push(ok(format!(
+                    "{name} {version}, generate the code with --ink-version {major}"
+                )));
+            }
+            None => checks.push(problem(format!(
+                "{name} {version} is used, but generated code needs ink! 3, 4 or 5"
+            ))),
+        }
+    }
+
+    let versions = deps::Versions {
+        ink: Some(ink_major.to_string()),
+        ..Default::default()
+    };
+    let missing = deps::of(mode, target, xvm_version, &versions)
+        .into_iter()
+        .filter(|required| dependency(required.name).is_none())
+        .map(|required| required.name)
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        checks.push(ok(format!("{} has all dependencies", path.display())));
+    } else {
+        checks.push(problem(format!(
+            "{} lacks dependencies: {}, add them with --write-deps",
+            path.display(),
+            missing.join(", ")
+        )));
+    }
+
+    (checks, ink_major)
+}
+
+/// Checks that the project manifest and installed tools are
+/// compatible with the code generated in the mode for the target
+pub fn check(manifest: &Path, mode: &Mode, target: &Target, xvm_version: XvmVersion) -> Vec<Check> {
+    let ink = match mode {
+        Mode::EvmToInk => !matches!(target, Target::FrontierRuntime),
+        Mode::InkToInk => true,
+        Mode::InkToEvm => return vec![ok("Solidity output has no requirements".to_owned())],
+    };
+
+    let (manifest_checks, ink_major) = check_manifest(manifest, mode, target, xvm_version, ink);
+    let mut checks = Vec::new();
+    if ink {
+        checks.push(check_cargo_contract(ink_major));
+    }
+
+    checks.extend(manifest_checks);
+    checks
+}
+
+/// Prints the checks, failing if any of them found a problem
+pub fn run(
+    manifest: &Path,
+    mode: &Mode,
+    target: &Target,
+    xvm_version: XvmVersion,
+) -> Result<(), Error> {
+    let checks = check(manifest, mode, target, xvm_version);
+    for check in &checks {
+        let status = if check.ok { "ok" } else { "problem" };
+        println!("{status}: {}", check.message);
+    }
+
+    match checks.iter().filter(|check| !check.ok).count() {
+        0 => Ok(()),
+        problems => Err(Error::Doctor(problems)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(major_version("^3.4.0"), Some(3));
+        assert_eq!(major_version("=3"), Some(3));
+        assert_eq!(
+            cargo_contract_version("cargo-contract 1.5.1-unknown-x86_64-unknown-linux-gnu\n"),
+            Some("1.5.1".to_owned())
+        );
+    }
+}
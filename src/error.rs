@@ -1,4 +1,5 @@
-use std::{io, path::PathBuf};
+use crate::warning::Warning;
+use std::{fmt, io, path::PathBuf};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -28,4 +29,129 @@ pub enum Error {
 
     #[error("metadata error: {0}")]
     Metadata(String),
+
+    #[error("unable to fetch ABI of {address}: {reason}")]
+    Fetch { address: String, reason: String },
+
+    #[error("invalid sumi:keep region: {0}")]
+    KeepRegion(String),
+
+    #[error("unable to append to the existing module: {0}")]
+    Append(String),
+
+    #[error("invalid config {0}")]
+    Config(String),
+
+    #[error("invalid manifest {0}")]
+    Manifest(String),
+
+    #[error("{0} compatibility problems found")]
+    Doctor(usize),
+
+    #[error("{0} lint warnings found")]
+    Lint(usize),
+
+    #[error("gas report error: {0}")]
+    GasReport(String),
+
+    #[error("template pack {pack}: {reason}")]
+    TemplatePack { pack: String, reason: String },
+
+    #[error("invalid docs file {0}")]
+    Docs(String),
+
+    #[error("module name is required in this mode, use --module-name")]
+    MissingModuleName,
+
+    #[error("invalid input pattern: {0}")]
+    InputPattern(#[from] glob::PatternError),
+
+    #[error("{first} and {second} would both be generated as module `{name}`, rename one of them")]
+    DuplicateModule {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("no functions of {module} are left to wrap: {reason}; pass --allow-empty to generate a stub with a raw call")]
+    EmptyModule { module: String, reason: String },
+
+    #[error("`{name}` is not a valid module name, try `{suggestion}`")]
+    InvalidModuleName { name: String, suggestion: String },
+
+    #[error(
+        "{path} was generated from ABI {found}, but the current one is {expected}, regenerate it"
+    )]
+    StaleModule {
+        path: PathBuf,
+        found: String,
+        expected: String,
+    },
+
+    #[error("{0} has no SOURCE_ABI_HASH, it was generated by an older version of Sumi")]
+    MissingStamp(PathBuf),
+
+    #[error("{0} differs from the regenerated output, regenerate it")]
+    OutdatedOutput(PathBuf),
+
+    #[error("line {line} of the human-readable ABI: {reason}")]
+    HumanReadableAbi { line: usize, reason: String },
+
+    #[error("{0}")]
+    Calldata(String),
+
+    #[error("cannot generate {adapter} adapter: {reason}")]
+    Adapter { adapter: String, reason: String },
+
+    #[error("unable to start worker threads")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    #[error("{failed} of {total} inputs failed to generate")]
+    Batch { failed: usize, total: usize },
+
+    #[error("{} problems found in the input:{}", .0.len(), list(.0))]
+    Params(Vec<ParamError>),
+
+    #[error("{} functions are not wrapped in strict mode:{}", .0.len(), list(.0))]
+    SkippedFunctions(Vec<Warning>),
+}
+
+/// Problem with a parameter of a function or message, reported along with all the others
+#[derive(Debug, thiserror::Error)]
+pub enum ParamError {
+    #[error("parameter {index} of `{function}` is malformed: {reason}")]
+    Malformed {
+        function: String,
+        index: usize,
+        reason: String,
+    },
+
+    #[error("parameter {index} of `{function}` has unsupported type `{ty}`")]
+    Unsupported {
+        function: String,
+        index: usize,
+        ty: String,
+    },
+
+    #[error("output {index} of `{function}` has unsupported type `{ty}`")]
+    UnsupportedOutput {
+        function: String,
+        index: usize,
+        ty: String,
+    },
+
+    #[error("parameter `{param}` of `{function}` cannot be mapped from `{ty}` to `{rust_type}`")]
+    UnsupportedOverride {
+        function: String,
+        param: String,
+        ty: String,
+        rust_type: String,
+    },
+}
+
+fn list<T: fmt::Display>(problems: &[T]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n  {problem}"))
+        .collect()
 }
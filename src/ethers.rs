@@ -0,0 +1,33 @@
+use crate::error::Error;
+use convert_case::{Case, Casing};
+use serde::Serialize;
+use tinytemplate::{format_unescaped, TinyTemplate};
+
+static ETHERS_TEMPLATE: &str = include_str!("../templates/ethers-client.txt");
+
+#[derive(Serialize)]
+struct Context<'a> {
+    module_name: &'a str,
+    name: String,
+    abi: String,
+}
+
+/// Name of the ethers-rs client file of the module
+pub fn file_name(module_name: &str) -> String {
+    format!("{}_ethers.rs", module_name.to_case(Case::Snake))
+}
+
+/// Renders ethers-rs module generating the client from the same ABI with `abigen!`
+pub fn render(json: &json::JsonValue, module_name: &str) -> Result<String, Error> {
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&format_unescaped);
+    template.add_template("ethers", ETHERS_TEMPLATE)?;
+
+    let context = Context {
+        module_name,
+        name: module_name.to_case(Case::UpperCamel),
+        abi: json.pretty(4),
+    };
+
+    Ok(template.render("ethers", &context)?)
+}
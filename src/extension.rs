@@ -0,0 +1,31 @@
+use crate::{error::Error, sol2ink::Model};
+use convert_case::{Case, Casing};
+use serde::Serialize;
+use tinytemplate::{format_unescaped, TinyTemplate};
+
+static EXTENSION_TEMPLATE: &str = include_str!("../templates/chain-extension.txt");
+
+#[derive(Serialize)]
+struct Context<'a> {
+    name: String,
+    model: &'a Model,
+}
+
+/// Name of the runtime module file of the module
+pub fn file_name(module_name: &str) -> String {
+    format!("{}_extension.rs", module_name.to_case(Case::Snake))
+}
+
+/// Renders runtime side chain extension decoding the EVM calls made by the wrapper
+pub fn render(model: &Model, module_name: &str) -> Result<String, Error> {
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&format_unescaped);
+    template.add_template("extension", EXTENSION_TEMPLATE)?;
+
+    let context = Context {
+        name: module_name.to_case(Case::UpperCamel),
+        model,
+    };
+
+    Ok(template.render("extension", &context)?)
+}
@@ -0,0 +1,289 @@
+use crate::error::Error;
+use convert_case::{Case, Casing};
+use hex::ToHex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Default Sourcify repository
+pub const SOURCIFY_URL: &str = "https://repo.sourcify.dev";
+
+/// Delay before the first retry, doubled on every subsequent attempt
+const BACKOFF: Duration = Duration::from_millis(500);
+
+/// Where verified ABIs are fetched from
+pub enum Source {
+    /// Etherscan compatible explorer API, i.e. Blockscout
+    Explorer {
+        url: String,
+        api_key: Option<String>,
+    },
+
+    /// Sourcify repository of the chain
+    Sourcify { url: String, chain_id: u64 },
+}
+
+pub struct Options {
+    pub source: Source,
+
+    /// Number of requests performed simultaneously
+    pub concurrency: usize,
+
+    /// Maximum number of requests per second across all workers
+    pub rate_limit: f64,
+
+    /// How many times a failed request is repeated
+    pub retries: u32,
+
+    /// Directory to cache successful responses in
+    pub cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fetched {
+    pub address: String,
+
+    /// Contract name as reported by the source
+    pub name: Option<String>,
+
+    /// ABI in JSON format
+    pub abi: String,
+}
+
+impl Fetched {
+    /// Module name derived from contract name, or address if it's unknown
+    pub fn module_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.to_case(Case::Snake),
+            None => format!(
+                "contract_{}",
+                self.address.trim_start_matches("0x").to_lowercase()
+            ),
+        }
+    }
+}
+
+enum Failure {
+    /// Request may succeed if repeated later
+    Transient(String),
+    Permanent(String),
+}
+
+/// Spreads requests evenly in time
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(rate_limit: f64) -> Self {
+        RateLimiter {
+            interval: if rate_limit > 0.0 {
+                Duration::from_secs_f64(1.0 / rate_limit)
+            } else {
+                Duration::ZERO
+            },
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn wait(&self) {
+        let now = Instant::now();
+        let slot = {
+            let mut next = self.next.lock().expect("rate limiter lock is poisoned");
+            let slot = (*next).max(now);
+            *next = slot + self.interval;
+            slot
+        };
+
+        thread::sleep(slot - now);
+    }
+}
+
+struct Fetcher<'a> {
+    options: &'a Options,
+    agent: ureq::Agent,
+    limiter: RateLimiter,
+}
+
+impl<'a> Fetcher<'a> {
+    fn fetch(&self, address: &str) -> Result<Fetched, Error> {
+        let error = |reason| Error::Fetch {
+            address: address.to_owned(),
+            reason,
+        };
+
+        if !is_address(address) {
+            return Err(error("not a valid contract address".to_owned()));
+        }
+
+        // API key is deliberately not a part of the cache key
+        let cache_path = self.options.cache_dir.as_ref().map(|dir| {
+            let mut hasher = Keccak256::new();
+            hasher.update(self.request_url(address, false).as_bytes());
+            let key: String = hasher.finalize().encode_hex();
+
+            dir.join(key).with_extension("json")
+        });
+
+        if let Some(cached) = cache_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+        {
+            return Ok(cached);
+        }
+
+        let mut attempt = 0;
+        let fetched = loop {
+            self.limiter.wait();
+
+            match self.request(address) {
+                Ok(fetched) => break fetched,
+                Err(Failure::Transient(reason)) if attempt < self.options.retries => {
+                    eprintln!("{address}: {reason}, retrying");
+                    thread::sleep(BACKOFF * 2u32.pow(attempt));
+                    attempt += 1;
+                }
+                Err(Failure::Transient(reason) | Failure::Permanent(reason)) => {
+                    return Err(error(reason))
+                }
+            }
+        };
+
+        if let Some(path) = cache_path {
+            fs::create_dir_all(path.parent().expect("cache file is inside a directory"))
+                .and_then(|_| fs::write(&path, serde_json::to_string(&fetched)?))
+                .map_err(|e| Error::WriteOutput { path, inner: e })?;
+        }
+
+        Ok(fetched)
+    }
+
+    fn request_url(&self, address: &str, with_key: bool) -> String {
+        match &self.options.source {
+            Source::Explorer { url, api_key } => {
+                let mut url =
+                    format!("{url}?module=contract&action=getsourcecode&address={address}");
+
+                if let (Some(api_key), true) = (api_key, with_key) {
+                    url.push_str("&apikey=");
+                    url.push_str(api_key);
+                }
+
+                url
+            }
+
+            Source::Sourcify { url, chain_id } => {
+                format!("{url}/contracts/full_match/{chain_id}/{address}/metadata.json")
+            }
+        }
+    }
+
+    fn get(&self, url: &str) -> Result<serde_json::Value, Failure> {
+        let response = match self.agent.get(url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, _)) if code == 429 || code >= 500 => {
+                return Err(Failure::Transient(format!("server responded with {code}")))
+            }
+            Err(ureq::Error::Status(code, _)) => {
+                return Err(Failure::Permanent(format!("server responded with {code}")))
+            }
+            Err(ureq::Error::Transport(e)) => return Err(Failure::Transient(e.to_string())),
+        };
+
+        let body = response
+            .into_string()
+            .map_err(|e| Failure::Transient(e.to_string()))?;
+
+        serde_json::from_str(&body)
+            .map_err(|e| Failure::Permanent(format!("malformed response: {e}")))
+    }
+
+    fn request(&self, address: &str) -> Result<Fetched, Failure> {
+        let response = self.get(&self.request_url(address, true))?;
+
+        match &self.options.source {
+            Source::Explorer { .. } => {
+                let result = &response["result"];
+
+                if response["status"] != "1" {
+                    let message = result.as_str().unwrap_or("unknown error").to_owned();
+                    return Err(if message.to_lowercase().contains("rate limit") {
+                        Failure::Transient(message)
+                    } else {
+                        Failure::Permanent(message)
+                    });
+                }
+
+                let abi = result[0]["ABI"].as_str().unwrap_or_default();
+                if !abi.trim_start().starts_with('[') {
+                    return Err(Failure::Permanent(format!("no verified ABI: {abi}")));
+                }
+
+                Ok(Fetched {
+                    address: address.to_owned(),
+                    name: result[0]["ContractName"]
+                        .as_str()
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_owned),
+                    abi: abi.to_owned(),
+                })
+            }
+
+            Source::Sourcify { .. } => {
+                let abi = &response["output"]["abi"];
+                if !abi.is_array() {
+                    return Err(Failure::Permanent(
+                        "metadata does not contain ABI".to_owned(),
+                    ));
+                }
+
+                Ok(Fetched {
+                    address: address.to_owned(),
+                    name: response["settings"]["compilationTarget"]
+                        .as_object()
+                        .and_then(|target| target.values().next())
+                        .and_then(|name| name.as_str())
+                        .map(str::to_owned),
+                    abi: abi.to_string(),
+                })
+            }
+        }
+    }
+}
+
+fn is_address(address: &str) -> bool {
+    address.strip_prefix("0x").map_or(false, |hex| {
+        hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Fetches ABIs of all addresses concurrently. Results are returned in the order of addresses.
+pub fn fetch_all(addresses: &[String], options: &Options) -> Result<Vec<Fetched>, Error> {
+    let fetcher = Fetcher {
+        options,
+        agent: ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build(),
+        limiter: RateLimiter::new(options.rate_limit),
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.concurrency.max(1))
+        .build()?;
+
+    pool.install(|| {
+        addresses
+            .par_iter()
+            .map(|address| fetcher.fetch(address))
+            .collect()
+    })
+}
@@ -0,0 +1,96 @@
+/// Selects ABI entries by name using glob patterns (`*` and `?` wildcards)
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    only: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Filter {
+    pub fn new(only: Vec<String>) -> Self {
+        Filter {
+            only,
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Rejects names matching any of the patterns, even if they match the `only` ones
+    pub fn excluding(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Empty filter matches everything
+    pub fn matches(&self, name: &str) -> bool {
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes()))
+        };
+
+        (self.only.is_empty() || matches_any(&self.only)) && !matches_any(&self.exclude)
+    }
+}
+
+/// On a mismatch only the last `*` is retried, taking one more byte of the name,
+/// so matching takes at most pattern length times name length steps
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        let filter = Filter::new(vec!["transfer*".to_owned(), "appro?e".to_owned()]);
+
+        assert!(filter.matches("transfer"));
+        assert!(filter.matches("transferFrom"));
+        assert!(filter.matches("approve"));
+        assert!(!filter.matches("allowance"));
+        assert!(!filter.matches("safeTransfer"));
+        assert!(Filter::default().matches("anything"));
+
+        let filter = filter.excluding(vec!["*From".to_owned()]);
+        assert!(filter.matches("transfer"));
+        assert!(!filter.matches("transferFrom"));
+    }
+
+    #[test]
+    fn many_stars() {
+        assert!(glob_match(b"*a*b*c*", b"xxaxxbxxcxx"));
+        assert!(!glob_match(b"*a*b*c*", b"xxaxxcxxbxx"));
+        assert!(glob_match(b"a*", b"a"));
+        assert!(!glob_match(b"a?", b"a"));
+
+        // Exponential if every star was retried
+        let pattern = "*a".repeat(16);
+        let name = format!("{}b", "a".repeat(64));
+        assert!(!glob_match(pattern.as_bytes(), name.as_bytes()));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::Error;
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Gas used by contract functions, collected from Hardhat or Foundry gas reports
+#[derive(Debug, Default, Clone)]
+pub struct Report {
+    /// Keyed by the function signature, i.e. `transfer(address,uint256)`,
+    /// or by the bare name if the report does not include signatures
+    gas: BTreeMap<String, u64>,
+}
+
+impl Report {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+            path: path.to_owned(),
+            inner: e,
+        })?;
+
+        Self::parse(&text).ok_or_else(|| {
+            Error::GasReport(format!("{} is not a known gas report", path.display()))
+        })
+    }
+
+    /// Parses Hardhat `gasReporterOutput.json`, Foundry JSON or
+    /// Foundry text table report, whichever the text is
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut report = Report::default();
+
+        match serde_json::from_str::<Value>(text) {
+            Ok(json) if json["info"]["methods"].is_object() => report.add_hardhat(&json),
+            Ok(Value::Array(contracts)) => report.add_foundry_json(&contracts),
+            Ok(_) => return None,
+            Err(_) => report.add_foundry_table(text),
+        }
+
+        (!report.gas.is_empty()).then_some(report)
+    }
+
+    /// Gas of the function, looked up by signature first and then by name.
+    /// If several contracts of the report have such function, maximum is used.
+    pub fn get(&self, name: &str, signature: &str) -> Option<u64> {
+        self.gas
+            .get(signature)
+            .or_else(|| self.gas.get(name))
+            .copied()
+    }
+
+    fn add(&mut self, key: &str, gas: u64) {
+        let entry = self.gas.entry(key.to_owned()).or_default();
+        *entry = (*entry).max(gas);
+    }
+
+    fn add_hardhat(&mut self, json: &Value) {
+        for method in json["info"]["methods"].as_object().into_iter().flatten() {
+            let method = method.1;
+            let max = method["gasData"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_u64)
+                .max();
+
+            if let (Some(signature), Some(max)) = (method["fnSig"].as_str(), max) {
+                self.add(signature, max);
+            }
+        }
+    }
+
+    fn add_foundry_json(&mut self, contracts: &[Value]) {
+        for contract in contracts {
+            for (key, value) in contract["functions"].as_object().into_iter().flatten() {
+                match value["max"].as_u64() {
+                    Some(max) => self.add(key, max),
+
+                    // Newer reports group overloads by name: `{ "name": { "signature": { .. } } }`
+                    None => {
+                        for (signature, value) in value.as_object().into_iter().flatten() {
+                            if let Some(max) = value["max"].as_u64() {
+                                self.add(signature, max);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rows of the table look like `| transfer | 2345 | 34567 | 34567 | 51234 | 12 |`
+    fn add_foundry_table(&mut self, text: &str) {
+        for line in text.lines() {
+            let cells: Vec<_> = line
+                .trim()
+                .trim_matches(|c| c == '|' || c == '│')
+                .split(|c| c == '|' || c == '│')
+                .map(str::trim)
+                .collect();
+
+            if let [name, _min, _avg, _median, max, _calls] = cells.as_slice() {
+                if let Ok(max) = max.parse() {
+                    self.add(name, max);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_reports() {
+        let hardhat = r#"{"info": {"methods": {"Token_a9059cbb": {
+            "fnSig": "transfer(address,uint256)", "gasData": [34567, 51234]
+        }}}}"#;
+
+        let report = Report::parse(hardhat).unwrap();
+        assert_eq!(
+            report.get("transfer", "transfer(address,uint256)"),
+            Some(51234)
+        );
+
+        let table = "| Function Name | min  | avg   | median | max   | # calls |\n\
+                     | transfer      | 2345 | 34567 | 34567  | 51234 | 12      |";
+
+        let report = Report::parse(table).unwrap();
+        assert_eq!(
+            report.get("transfer", "transfer(address,uint256)"),
+            Some(51234)
+        );
+        assert_eq!(report.get("approve", "approve(address,uint256)"), None);
+    }
+}
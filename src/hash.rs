@@ -0,0 +1,57 @@
+use sha3::{Digest, Keccak256};
+
+/// Hash function selectors, topics and message digests are computed with
+pub trait Hasher {
+    fn hash(&self, data: &[u8]) -> [u8; 32];
+}
+
+/// Keccak256, the hash function of the EVM
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Keccak;
+
+impl Hasher for Keccak {
+    fn hash(&self, data: &[u8]) -> [u8; 32] {
+        Keccak256::digest(data).into()
+    }
+}
+
+/// Header of EIP-191 personal messages, followed by the decimal length of the message
+pub const EIP191_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// First 4 bytes of the hash of the function signature
+pub fn selector(hasher: &impl Hasher, signature: &str) -> [u8; 4] {
+    let hash = hasher.hash(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Hash of the event signature
+pub fn topic(hasher: &impl Hasher, signature: &str) -> [u8; 32] {
+    hasher.hash(signature.as_bytes())
+}
+
+/// EIP-191 digest of the message, which `personal_sign` signs and
+/// EVM contracts recover the signer from
+pub fn eip191_digest(hasher: &impl Hasher, message: &[u8]) -> [u8; 32] {
+    let mut prefixed = EIP191_PREFIX.to_vec();
+    prefixed.extend_from_slice(message.len().to_string().as_bytes());
+    prefixed.extend_from_slice(message);
+    hasher.hash(&prefixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::ToHex;
+
+    #[test]
+    fn keccak_digests() {
+        assert_eq!(
+            selector(&Keccak, "transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert_eq!(
+            eip191_digest(&Keccak, b"hello").encode_hex::<String>(),
+            "50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::Error;
+use serde_json::{json, Value};
+
+/// Parser of a single declaration, i.e. `function transfer(address to, uint256 amount) returns (bool)`
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.skip_whitespace();
+        self.rest().is_empty()
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+
+        match self.peek() {
+            Some(found) => Err(format!("expected `{expected}`, found `{found}`")),
+            None => Err(format!("expected `{expected}`, found the end of the line")),
+        }
+    }
+
+    /// Identifier or keyword, empty if there is none
+    fn word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(rest.len());
+
+        self.position += length;
+        &rest[..length]
+    }
+
+    fn peek_word(&mut self) -> &'a str {
+        let position = self.position;
+        let word = self.word();
+        self.position = position;
+        word
+    }
+
+    /// Parenthesized parameter list
+    fn params(&mut self) -> Result<Vec<Value>, String> {
+        self.expect('(')?;
+        let mut params = Vec::new();
+        if self.eat(')') {
+            return Ok(params);
+        }
+
+        loop {
+            params.push(self.param()?);
+            if !self.eat(',') {
+                self.expect(')')?;
+                return Ok(params);
+            }
+        }
+    }
+
+    fn param(&mut self) -> Result<Value, String> {
+        let mut param = json!({});
+        let mut ty = if self.peek() == Some('(') || self.peek_word() == "tuple" {
+            self.word();
+            param["components"] = Value::Array(self.params()?);
+            "tuple".to_owned()
+        } else {
+            match self.word() {
+                "" => return Err("expected a type".to_owned()),
+                // Shorthands of the canonical types
+                "uint" => "uint256".to_owned(),
+                "int" => "int256".to_owned(),
+                "byte" => "bytes1".to_owned(),
+                ty => ty.to_owned(),
+            }
+        };
+
+        while self.eat('[') {
+            let size = self.word();
+            if !size.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format!("invalid array size `{size}`"));
+            }
+
+            self.expect(']')?;
+            ty.push_str(&format!("[{size}]"));
+        }
+
+        param["type"] = Value::String(ty);
+        param["name"] = Value::String(String::new());
+        loop {
+            match self.word() {
+                "" => break,
+                "indexed" => param["indexed"] = Value::Bool(true),
+                // `address payable` is still an address
+                "memory" | "calldata" | "storage" | "payable" => {}
+                name => param["name"] = Value::String(name.to_owned()),
+            }
+        }
+
+        Ok(param)
+    }
+
+    /// State mutability and the outputs following the function parameters
+    fn function_tail(&mut self) -> Result<(String, Vec<Value>), String> {
+        let mut mutability = "nonpayable";
+        let mut outputs = Vec::new();
+        while !self.is_done() {
+            match self.word() {
+                "view" | "constant" => mutability = "view",
+                "pure" => mutability = "pure",
+                "payable" => mutability = "payable",
+                "nonpayable" | "external" | "public" | "virtual" | "override" => {}
+                "returns" => outputs = self.params()?,
+                "" => return Err(format!("unexpected `{}`", self.rest())),
+                word => return Err(format!("unexpected `{word}`")),
+            }
+        }
+
+        Ok((mutability.to_owned(), outputs))
+    }
+
+    fn item(&mut self) -> Result<Value, String> {
+        let keyword = self.peek_word();
+        let keyword = match keyword {
+            "function" | "event" | "error" | "constructor" | "fallback" | "receive" => self.word(),
+            // Bare signatures are functions, i.e. `transfer(address,uint256)`
+            _ => "function",
+        };
+
+        let item = match keyword {
+            "function" => {
+                let name = self.word();
+                if name.is_empty() {
+                    return Err("expected the function name".to_owned());
+                }
+
+                let inputs = self.params()?;
+                let (mutability, outputs) = self.function_tail()?;
+                json!({
+                    "type": "function",
+                    "name": name,
+                    "inputs": inputs,
+                    "outputs": outputs,
+                    "stateMutability": mutability,
+                })
+            }
+            "event" => {
+                let name = self.word();
+                let mut inputs = self.params()?;
+                for input in &mut inputs {
+                    if input.get("indexed").is_none() {
+                        input["indexed"] = Value::Bool(false);
+                    }
+                }
+
+                let anonymous = match self.word() {
+                    "anonymous" => true,
+                    "" => false,
+                    word => return Err(format!("unexpected `{word}`")),
+                };
+                json!({
+                    "type": "event",
+                    "name": name,
+                    "inputs": inputs,
+                    "anonymous": anonymous,
+                })
+            }
+            "error" => json!({
+                "type": "error",
+                "name": self.word(),
+                "inputs": self.params()?,
+            }),
+            "constructor" => {
+                let inputs = self.params()?;
+                let (mutability, _) = self.function_tail()?;
+                json!({
+                    "type": "constructor",
+                    "inputs": inputs,
+                    "stateMutability": mutability,
+                })
+            }
+            // `fallback` and `receive`
+            kind => {
+                if self.peek() == Some('(') {
+                    self.params()?;
+                }
+
+                let (mutability, _) = self.function_tail()?;
+                json!({ "type": kind, "stateMutability": mutability })
+            }
+        };
+
+        if self.is_done() {
+            Ok(item)
+        } else {
+            Err(format!("unexpected `{}`", self.rest()))
+        }
+    }
+}
+
+/// Converts ethers style human-readable ABI, a declaration per line, to the JSON ABI.
+/// Empty lines and `//` comments are skipped.
+pub fn parse(source: &str) -> Result<String, Error> {
+    let mut items = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let line = line.trim_end_matches(';').trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parser = Parser {
+            text: line,
+            position: 0,
+        };
+
+        items.push(parser.item().map_err(|reason| Error::HumanReadableAbi {
+            line: index + 1,
+            reason,
+        })?);
+    }
+
+    Ok(Value::Array(items).to_string())
+}
+
+/// Converts input that is not a JSON object: human-readable ABI as text or as a JSON
+/// array of strings is parsed, JSON ABI is returned as is
+pub fn read(source: String) -> Result<String, Error> {
+    if !source.trim_start().starts_with('[') {
+        return parse(&source);
+    }
+
+    match serde_json::from_str::<Vec<String>>(&source) {
+        Ok(lines) if !lines.is_empty() => parse(&lines.join("\n")),
+        _ => Ok(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_declarations() {
+        let abi: Value = serde_json::from_str(
+            &parse(
+                "function transfer(address to, uint amount) external returns (bool)\n\
+                 // comment\n\
+                 event Transfer(address indexed from, address indexed to, uint256 value);\n\
+                 balanceOf(address) view returns (uint256)\n\
+                 function swap((address tokenIn, uint24 fee)[] calldata paths) payable",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(abi[0]["inputs"][1]["type"], "uint256");
+        assert_eq!(abi[0]["outputs"][0]["type"], "bool");
+        assert_eq!(abi[1]["inputs"][0]["indexed"], true);
+        assert_eq!(abi[1]["inputs"][2]["indexed"], false);
+        assert_eq!(abi[2]["stateMutability"], "view");
+        assert_eq!(abi[3]["inputs"][0]["type"], "tuple[]");
+        assert_eq!(abi[3]["inputs"][0]["components"][1]["name"], "fee");
+        assert_eq!(abi[3]["stateMutability"], "payable");
+
+        assert!(matches!(
+            parse("function f(address,"),
+            Err(Error::HumanReadableAbi { line: 1, .. })
+        ));
+    }
+}
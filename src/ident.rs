@@ -0,0 +1,97 @@
+use crate::error::Error;
+
+/// Strict and reserved keywords, which cannot name a module, a field or a function
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Field or variable name, raw if it is a keyword, i.e. `type` -> `r#type`
+pub fn field(name: &str) -> String {
+    match name {
+        // Cannot be raw identifiers
+        "self" | "super" | "crate" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_owned(),
+    }
+}
+
+/// Snake case keeping digits next to letters, i.e. `IERC20` -> `ierc20`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if matches!(previous, Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            snake.push(c);
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+
+        previous = Some(c);
+    }
+
+    snake.trim_end_matches('_').to_owned()
+}
+
+/// Checks that the name is a snake case Rust identifier usable as a module name
+pub fn validate_module_name(name: &str) -> Result<(), Error> {
+    let is_snake_case = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if is_snake_case && name != "_" && !KEYWORDS.contains(&name) {
+        return Ok(());
+    }
+
+    Err(Error::InvalidModuleName {
+        name: name.to_owned(),
+        suggestion: suggestion(name),
+    })
+}
+
+/// Closest valid module name, i.e. `My-Token` -> `my_token`
+fn suggestion(name: &str) -> String {
+    let snake = snake_case(name);
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("contract_{snake}")
+    } else if KEYWORDS.contains(&snake.as_str()) {
+        format!("{snake}_contract")
+    } else {
+        snake
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_module_names() {
+        assert!(validate_module_name("erc20").is_ok());
+        assert!(validate_module_name("_private").is_ok());
+
+        for (name, expected) in [
+            ("My-Token", "my_token"),
+            ("ERC20Token", "erc20_token"),
+            ("1inch", "contract_1inch"),
+            ("type", "type_contract"),
+        ] {
+            match validate_module_name(name) {
+                Err(Error::InvalidModuleName { suggestion, .. }) => {
+                    assert_eq!(suggestion, expected)
+                }
+                other => panic!("{name}: unexpected {other:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{config::DEFAULT_CONFIG, error::Error};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+static CONFIG: &str = include_str!("../templates/init-sumi.toml");
+static GENERATE_SCRIPT: &str = include_str!("../templates/init-generate.sh");
+
+/// Directory the ABIs of the wrapped contracts are put to
+const ABI_DIR: &str = "abis";
+
+/// Creates starter project structure in the directory: config, ABI directory and a script
+/// generating a `cargo contract` project per ABI. Existing files are kept unless `force`
+/// is set. Returns the created files.
+pub fn run(dir: &Path, force: bool) -> Result<Vec<PathBuf>, Error> {
+    let files = [
+        (dir.join(DEFAULT_CONFIG), CONFIG),
+        (dir.join("generate.sh"), GENERATE_SCRIPT),
+        (dir.join(ABI_DIR).join(".gitkeep"), ""),
+    ];
+
+    let mut created = Vec::new();
+    for (path, contents) in files {
+        if path.exists() && !force {
+            eprintln!("{} already exists, skipping", path.display());
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::WriteOutput {
+                path: parent.to_path_buf(),
+                inner: e,
+            })?;
+        }
+
+        fs::write(&path, contents).map_err(|e| Error::WriteOutput {
+            path: path.clone(),
+            inner: e,
+        })?;
+
+        created.push(path);
+    }
+
+    Ok(created)
+}
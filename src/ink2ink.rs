@@ -0,0 +1,333 @@
+use crate::{error::Error, ident};
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use tinytemplate::TinyTemplate;
+
+static MODULE_TEMPLATE: &str = include_str!("../templates/ink-caller.txt");
+
+#[derive(Serialize)]
+struct Field {
+    name: Option<String>,
+    ty: String,
+}
+
+#[derive(Serialize)]
+struct Variant {
+    name: String,
+    named: bool,
+    fields: Vec<Field>,
+}
+
+/// Custom type defined in the generated module
+#[derive(Serialize, Default)]
+struct Definition {
+    name: String,
+    is_enum: bool,
+    named: bool,
+    fields: Vec<Field>,
+    variants: Vec<Variant>,
+}
+
+#[derive(Serialize)]
+struct Arg {
+    /// Name of the argument, raw if it is a keyword
+    name: String,
+    ty: String,
+}
+
+#[derive(Serialize)]
+struct Message {
+    label: String,
+
+    /// Name of the caller method, the label without the trait path
+    name: String,
+    docs: Vec<String>,
+    args: Vec<Arg>,
+    return_type: String,
+    payable: bool,
+
+    /// Comma separated selector bytes, i.e. `0xdb, 0x63, 0x75, 0xa8`
+    selector: String,
+}
+
+#[derive(Serialize)]
+struct Module {
+    contract_name: String,
+    caller_name: String,
+    definitions: Vec<Definition>,
+    messages: Vec<Message>,
+}
+
+fn members(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+/// Converts metadata types to their Rust names, collecting
+/// definitions of custom types along the way.
+struct TypeRenderer<'m> {
+    types: HashMap<u64, &'m Value>,
+    definitions: BTreeMap<String, Definition>,
+}
+
+impl<'m> TypeRenderer<'m> {
+    fn new(metadata: &'m Value) -> Self {
+        let types = members(&metadata["types"])
+            .filter_map(|ty| Some((ty["id"].as_u64()?, &ty["type"])))
+            .collect();
+
+        TypeRenderer {
+            types,
+            definitions: BTreeMap::new(),
+        }
+    }
+
+    fn name(&mut self, id: &Value) -> Result<String, Error> {
+        let id = id
+            .as_u64()
+            .ok_or_else(|| Error::Metadata(format!("invalid type id {id}")))?;
+
+        let ty = *self
+            .types
+            .get(&id)
+            .ok_or_else(|| Error::Metadata(format!("type {id} is not in the registry")))?;
+
+        let def = &ty["def"];
+        let path = members(&ty["path"]).filter_map(Value::as_str).collect_vec();
+
+        let params = members(&ty["params"])
+            .map(|param| self.name(&param["type"]))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(match (path.as_slice(), def) {
+            (["ink_env", "types", name], _) => name.to_string(),
+            (["Option"], _) | (["Result"], _) => format!("{}<{}>", path[0], params.join(", ")),
+
+            (_, def) if def["primitive"].is_string() => match def["primitive"].as_str() {
+                Some("str") => "String".to_owned(),
+                Some(primitive) => primitive.to_owned(),
+                None => unreachable!(),
+            },
+
+            (_, def) if def["array"].is_object() => format!(
+                "[{}; {}]",
+                self.name(&def["array"]["type"])?,
+                def["array"]["len"]
+            ),
+
+            (_, def) if def["sequence"].is_object() => {
+                format!("Vec<{}>", self.name(&def["sequence"]["type"])?)
+            }
+
+            (_, def) if def["compact"].is_object() => {
+                format!("scale::Compact<{}>", self.name(&def["compact"]["type"])?)
+            }
+
+            (_, def) if def["tuple"].is_array() => {
+                let fields = members(&def["tuple"])
+                    .map(|field| self.name(field))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match fields.as_slice() {
+                    [single] => format!("({single},)"),
+                    _ => format!("({})", fields.join(", ")),
+                }
+            }
+
+            ([.., name], def) if params.is_empty() => {
+                let name = name.to_case(Case::UpperCamel);
+                if !self.definitions.contains_key(&name) {
+                    // Placeholder prevents endless recursion on recursive types
+                    self.definitions.insert(name.clone(), Definition::default());
+
+                    let definition = self.define(&name, def)?;
+                    self.definitions.insert(name.clone(), definition);
+                }
+
+                name
+            }
+
+            _ => {
+                return Err(Error::Metadata(format!(
+                    "type {id} ({}) is not supported",
+                    path.join("::")
+                )))
+            }
+        })
+    }
+
+    fn fields(&mut self, fields: &Value) -> Result<(bool, Vec<Field>), Error> {
+        let fields = members(fields)
+            .map(|field| {
+                Ok(Field {
+                    name: field["name"].as_str().map(str::to_owned),
+                    ty: self.name(&field["type"])?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let named = !fields.is_empty() && fields.iter().all(|field| field.name.is_some());
+        Ok((named, fields))
+    }
+
+    fn define(&mut self, name: &str, def: &Value) -> Result<Definition, Error> {
+        if def["composite"].is_object() {
+            let (named, fields) = self.fields(&def["composite"]["fields"])?;
+
+            Ok(Definition {
+                name: name.to_owned(),
+                named,
+                fields,
+                ..Definition::default()
+            })
+        } else if def["variant"].is_object() {
+            let variants = members(&def["variant"]["variants"])
+                .map(|variant| {
+                    let (named, fields) = self.fields(&variant["fields"])?;
+
+                    Ok(Variant {
+                        name: variant["name"].as_str().unwrap_or_default().to_owned(),
+                        named,
+                        fields,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            Ok(Definition {
+                name: name.to_owned(),
+                is_enum: true,
+                variants,
+                ..Definition::default()
+            })
+        } else {
+            Err(Error::Metadata(format!("type {name} is not supported")))
+        }
+    }
+}
+
+/// Method or argument name of the label, i.e. `PSP22::balance_of` -> `balance_of`
+fn method_name(label: &str) -> String {
+    let name = label.rsplit("::").next().unwrap_or_default();
+    ident::field(&name.to_case(Case::Snake))
+}
+
+/// Renders typed cross-contract caller of the ink! contract described by the metadata
+pub fn render(source: &str, module_name: Option<&str>) -> Result<String, Error> {
+    let metadata: Value = serde_json::from_str(source)?;
+    let project = &metadata["V3"];
+    if !project.is_object() {
+        return Err(Error::Metadata(
+            "only V3 contract metadata is supported".to_owned(),
+        ));
+    }
+
+    let mut types = TypeRenderer::new(project);
+    let messages = members(&project["spec"]["messages"])
+        .map(|message| {
+            let label = message["label"].as_str().unwrap_or_default().to_owned();
+
+            let args = members(&message["args"])
+                .map(|arg| {
+                    Ok(Arg {
+                        name: method_name(arg["label"].as_str().unwrap_or_default()),
+                        ty: types.name(&arg["type"]["type"])?,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let return_type = match &message["returnType"] {
+                Value::Null => "()".to_owned(),
+                return_type => types.name(&return_type["type"])?,
+            };
+
+            let selector = message["selector"].as_str().unwrap_or_default();
+            let selector = hex::decode(selector.trim_start_matches("0x"))
+                .ok()
+                .filter(|bytes| bytes.len() == 4)
+                .ok_or_else(|| {
+                    Error::Metadata(format!("invalid selector {selector} of message {label}"))
+                })?;
+
+            Ok(Message {
+                name: method_name(&label),
+                docs: members(&message["docs"])
+                    .filter_map(Value::as_str)
+                    .map(str::to_owned)
+                    .collect(),
+                args,
+                return_type,
+                payable: message["payable"].as_bool().unwrap_or_default(),
+                selector: selector.iter().map(|b| format!("{b:#04x}")).join(", "),
+                label,
+            })
+        })
+        .collect::<Result<Vec<Message>, Error>>()?;
+
+    // Messages of different traits may share a name, those keep the trait path
+    let names = messages.iter().map(|message| message.name.clone()).counts();
+    let messages = messages
+        .into_iter()
+        .map(|message| match names[&message.name] {
+            1 => message,
+            _ => Message {
+                name: ident::field(&message.label.replace("::", "_").to_case(Case::Snake)),
+                ..message
+            },
+        })
+        .collect();
+
+    let contract_name = metadata["contract"]["name"]
+        .as_str()
+        .unwrap_or("contract")
+        .to_owned();
+
+    let module = Module {
+        caller_name: format!(
+            "{}Caller",
+            module_name
+                .unwrap_or(&contract_name)
+                .to_case(Case::UpperCamel)
+        ),
+        contract_name,
+        definitions: types.definitions.into_values().collect(),
+        messages,
+    };
+
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&tinytemplate::format_unescaped);
+    template.add_template("module", MODULE_TEMPLATE)?;
+
+    Ok(template.render("module", &module)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_trait_messages() {
+        let metadata = r#"{
+            "contract": {"name": "flipper"},
+            "V3": {
+                "types": [{"id": 0, "type": {"def": {"primitive": "bool"}}}],
+                "spec": {"messages": [
+                    {"label": "Flip::flip", "selector": "0x633aa551", "payable": false, "docs": [],
+                        "args": [{"label": "type", "type": {"type": 0}}], "returnType": null},
+                    {"label": "Get::get", "selector": "0x2f865bd9", "payable": false, "docs": [],
+                        "args": [], "returnType": {"type": 0}},
+                    {"label": "Other::get", "selector": "0x11223344", "payable": false, "docs": [],
+                        "args": [], "returnType": {"type": 0}}
+                ]}
+            }
+        }"#;
+
+        let code = render(metadata, None).unwrap();
+        assert!(code.contains("    pub fn flip(\n        &self,\n        r#type: bool,\n"));
+        assert!(code.contains(".push_arg(r#type)"));
+        assert!(code.contains("    pub fn get_get("));
+        assert!(code.contains("    pub fn other_get("));
+        assert!(!code.contains("::flip("));
+    }
+}
@@ -5,7 +5,7 @@ use serde::Serialize;
 use std::{cell::RefCell, collections::HashMap, io::Read, rc::Rc};
 use tinytemplate::{error::Error::GenericError, TinyTemplate};
 
-use crate::error::Error;
+use crate::error::{Error, ParamError};
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct EvmType {
@@ -36,29 +36,32 @@ struct Context<'template> {
 }
 
 impl<'template> Context<'template> {
-    fn new(project: Rc<InkProject>) -> Self {
+    /// Renders the definition of the type, failures are described like unsupported types
+    fn render<C: Serialize>(&self, name: &str, value: &C) -> Result<String, String> {
+        self.templates
+            .render(name, value)
+            .map_err(|e| format!("{name} definition ({e})"))
+    }
+
+    fn new(project: Rc<InkProject>) -> Result<Self, Error> {
         let mut templates = TinyTemplate::new();
         templates.set_default_formatter(&tinytemplate::format_unescaped);
-        templates
-            .add_template("struct", include_str!("../templates/solidity-struct.txt"))
-            .unwrap();
-        templates
-            .add_template("enum", include_str!("../templates/solidity-enum.txt"))
-            .unwrap();
-        templates
-            .add_template("encoder", include_str!("../templates/solidity-encoder.txt"))
-            .unwrap();
+        templates.add_template("struct", include_str!("../templates/solidity-struct.txt"))?;
+        templates.add_template("enum", include_str!("../templates/solidity-enum.txt"))?;
+        templates.add_template("encoder", include_str!("../templates/solidity-encoder.txt"))?;
 
         templates.add_formatter("path", format_path);
 
-        Context { project, templates }
+        Ok(Context { project, templates })
     }
 }
 
 fn format_path(value: &serde_json::Value, buffer: &mut String) -> tinytemplate::error::Result<()> {
     let path: String = value
         .as_array()
-        .expect("not an array")
+        .ok_or_else(|| GenericError {
+            msg: format!("path {value} is not an array"),
+        })?
         .iter()
         .filter_map(|v| v.as_str())
         .join("_");
@@ -84,25 +87,27 @@ impl EvmTypeRegistry {
         self.mapping.insert(id, ty);
     }
 
+    /// Converts the type and the types it consists of. Returns
+    /// the description of the first unsupported one on failure.
     fn convert_type(
         &mut self,
         id: u32,
         ty: &Type<PortableForm>,
         context: &Context,
-    ) -> Option<EvmType> {
-        let mut lookup_reference_or_insert = |id| {
+    ) -> Result<EvmType, String> {
+        let mut lookup_reference_or_insert = |id: u32| -> Result<String, String> {
             if let Some(ty) = self.lookup(id) {
-                Some(ty.reference.clone())
+                Ok(ty.reference.clone())
             } else {
                 let ty = context
                     .project
                     .registry()
                     .resolve(id)
-                    .expect("should exist");
+                    .ok_or_else(|| format!("unknown type id {id}"))?;
                 let new_type = self.convert_type(id, ty, context)?;
                 let reference = new_type.reference.clone();
                 self.insert(id, new_type);
-                Some(reference)
+                Ok(reference)
             }
         };
 
@@ -124,27 +129,27 @@ impl EvmTypeRegistry {
              fields: Box<dyn Iterator<Item = scale_info::Field<PortableForm>>>| {
                 let fields = fields
                     .enumerate()
-                    .map(|(index, field)| {
+                    .map(|(index, field)| -> Result<Field, String> {
                         let id = field.ty().id();
 
-                        Field {
+                        Ok(Field {
                             name: field
                                 .name()
                                 .cloned()
                                 .unwrap_or_else(|| format!("f{}", index)),
-                            ty: lookup_reference_or_insert(id).unwrap_or_default(),
-                        }
+                            ty: lookup_reference_or_insert(id)?,
+                        })
                     })
-                    .collect_vec();
+                    .collect::<Result<Vec<_>, String>>()?;
 
-                Struct { path, fields }
+                Ok::<_, String>(Struct { path, fields })
             };
 
-        Some(match ty.type_def() {
+        Ok(match ty.type_def() {
             TypeDef::Primitive(primitive) => EvmType {
                 reference: match primitive {
                     TypeDefPrimitive::Bool => "bool",
-                    TypeDefPrimitive::Char => return None, // TODO
+                    TypeDefPrimitive::Char => return Err("char".to_owned()),
                     TypeDefPrimitive::Str => "string",
                     TypeDefPrimitive::U8 => "uint8",
                     TypeDefPrimitive::U16 => "uint16",
@@ -168,8 +173,8 @@ impl EvmTypeRegistry {
                 let reference = lookup_reference_or_insert(id)?;
                 let size = array.len();
 
-                // Special handling of byte arrays
-                if reference == "uint8" && size <= 32 {
+                // Special handling of byte arrays, longer ones stay `uint8[N]`
+                if reference == "uint8" && (1..=32).contains(&size) {
                     EvmType {
                         reference: format!("bytes{size}"),
                         ..EvmType::default()
@@ -186,19 +191,19 @@ impl EvmTypeRegistry {
                 let st = fields_to_struct(
                     ty.path().clone(),
                     Box::new(composite.fields().iter().cloned()),
-                );
+                )?;
 
                 EvmType {
                     // Tuples are not first class citizens of Solidity.
                     // Hence, we are forced to define them as structs.
-                    definition: Some(context.templates.render("struct", &st).unwrap()),
+                    definition: Some(context.render("struct", &st)?),
 
                     reference: ty.path().segments().join("_"),
 
                     // Structures should be declared using `memory` specifier
                     modifier: Some("memory".to_owned()),
 
-                    encoder: Some(context.templates.render("encoder", &st).unwrap()),
+                    encoder: Some(context.render("encoder", &st)?),
 
                     ..EvmType::default()
                 }
@@ -211,12 +216,12 @@ impl EvmTypeRegistry {
                         Box::new(tuple.fields().iter().map(|id| {
                             scale_info::Field::<PortableForm>::new(None, *id, None, vec![])
                         })),
-                    );
+                    )?;
 
                 EvmType {
                     // Tuples are not first class citizens of Solidity.
                     // Hence, we are forced to define them as structs.
-                    definition: Some(context.templates.render("struct", &st).unwrap()),
+                    definition: Some(context.render("struct", &st)?),
 
                     // Structures should be referred using `memory` specifier
                     reference: ty.path().segments().join("_") + " memory",
@@ -234,21 +239,78 @@ impl EvmTypeRegistry {
 
                 // Solidity does not support non-default variant discriminants :(
                 if !default_indices {
-                    return None; // TODO report error
+                    return Err(format!(
+                        "enum {} with custom discriminants",
+                        ty.path().segments().join("::")
+                    ));
                 }
 
                 // Algebraic enums would require complex discriminant and substructure handling :(
                 // Currently we just encode them as C-style POD enums completely omitting fields
                 EvmType {
-                    definition: Some(context.templates.render("enum", &ty).unwrap()),
+                    definition: Some(context.render("enum", &ty)?),
                     reference: ty.path().segments().join("_"),
                     ..EvmType::default()
                 }
             }
 
-            _ => return None, // todo!(),
+            TypeDef::Sequence(_) => return Err("sequence".to_owned()),
+            TypeDef::Compact(_) => return Err("compact integer".to_owned()),
+            TypeDef::BitSequence(_) => return Err("bit sequence".to_owned()),
         })
     }
+
+    /// Converts types of the arguments and outputs of all messages the module wraps,
+    /// so problems with all of them are reported at once before anything is rendered
+    fn convert_messages(&mut self, context: &Context) -> Result<(), Error> {
+        let mut problems = Vec::new();
+        let mut convert = |id: u32| -> Result<(), String> {
+            if self.lookup(id).is_none() {
+                let ty = context
+                    .project
+                    .registry()
+                    .resolve(id)
+                    .ok_or_else(|| format!("unknown type id {id}"))?;
+                let new_type = self.convert_type(id, ty, context)?;
+                self.insert(id, new_type);
+            }
+
+            Ok(())
+        };
+
+        // Only state changing messages are wrapped for now
+        for message in context.project.spec().messages() {
+            if !message.mutates() {
+                continue;
+            }
+
+            for (index, arg) in message.args().iter().enumerate() {
+                if let Err(ty) = convert(arg.ty().ty().id()) {
+                    problems.push(ParamError::Unsupported {
+                        function: message.label().clone(),
+                        index,
+                        ty,
+                    });
+                }
+            }
+
+            if let Some(output) = message.return_type().opt_type() {
+                if let Err(ty) = convert(output.ty().id()) {
+                    problems.push(ParamError::UnsupportedOutput {
+                        function: message.label().clone(),
+                        index: 0,
+                        ty,
+                    });
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Params(problems))
+        }
+    }
 }
 
 pub fn render(reader: &mut dyn Read, module_name: &Option<String>) -> Result<String, Error> {
@@ -271,8 +333,10 @@ pub fn render(reader: &mut dyn Read, module_name: &Option<String>) -> Result<Str
 
     template.add_formatter("path", format_path);
 
-    let evm_registry = Rc::new(RefCell::new(EvmTypeRegistry::new()));
-    let context = Context::new(project.clone());
+    let context = Context::new(project.clone())?;
+    let mut evm_registry = EvmTypeRegistry::new();
+    evm_registry.convert_messages(&context)?;
+    let evm_registry = Rc::new(RefCell::new(evm_registry));
 
     let registry = evm_registry.clone();
     template.add_predicate("mapped", move |id| {
@@ -302,13 +366,19 @@ pub fn render(reader: &mut dyn Read, module_name: &Option<String>) -> Result<Str
                     Some("definition") => ty.definition.as_ref().unwrap_or(&empty),
                     Some("modifier") => ty.modifier.as_ref().unwrap_or(&empty),
                     Some("encoder") => ty.encoder.as_ref().unwrap_or(&empty),
-                    _ => panic!("type formatter must come with an argument"),
+                    _ => {
+                        return Err(GenericError {
+                            msg: format!("type formatter needs an argument, got {arg:?}"),
+                        })
+                    }
                 });
+
+                Ok(())
             };
 
             let mut registry = evm_registry.borrow_mut();
             match registry.lookup_mut(id) {
-                Some(ty) => write_buffer(ty, buffer),
+                Some(ty) => write_buffer(ty, buffer)?,
                 None => {
                     let ty =
                         context
@@ -318,8 +388,13 @@ pub fn render(reader: &mut dyn Read, module_name: &Option<String>) -> Result<Str
                             .ok_or_else(|| GenericError {
                                 msg: format!("invalid id {id:?}"),
                             })?;
-                    let mut new_type = registry.convert_type(id, ty, &context).unwrap();
-                    write_buffer(&mut new_type, buffer);
+                    let new_type =
+                        registry
+                            .convert_type(id, ty, &context)
+                            .map_err(|ty| GenericError {
+                                msg: format!("unsupported type {ty}"),
+                            })?;
+                    write_buffer(&new_type, buffer)?;
                     registry.insert(id, new_type);
                 }
             }
@@ -0,0 +1,43 @@
+use crate::error::Error;
+use memmap2::Mmap;
+use std::{fs, io::Read, ops::Deref, path::Path};
+
+/// Contents of an input file
+pub enum Input {
+    Mapped(Mmap),
+    Buffered(Vec<u8>),
+}
+
+impl Deref for Input {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Input::Mapped(map) => map,
+            Input::Buffered(buffer) => buffer,
+        }
+    }
+}
+
+/// Opens input file as a memory map, so large inputs do not have
+/// to be copied into memory. Files that cannot be mapped (pipes,
+/// special files) are read into a buffer instead.
+pub fn open(path: &Path) -> Result<Input, Error> {
+    let read_error = |e| Error::ReadInput {
+        path: path.to_owned(),
+        inner: e,
+    };
+
+    let mut file = fs::File::open(path).map_err(read_error)?;
+
+    // Safety: the map is read only and lives only for the duration of generation.
+    // Input files being truncated by someone else while we read them is not supported.
+    match unsafe { Mmap::map(&file) } {
+        Ok(map) => Ok(Input::Mapped(map)),
+        Err(_) => {
+            let mut buffer = Vec::new();
+            file.read_to_end(&mut buffer).map_err(read_error)?;
+            Ok(Input::Buffered(buffer))
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    artifact,
+    cli::Target,
+    error::Error,
+    sol2ink::{self, canonical_type, signature, CustomTemplates, Generator, Options},
+};
+use clap::ValueEnum;
+use itertools::Itertools;
+use json::JsonValue;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Format of the input, guessed from its top level keys
+fn detect_format(input: &Value) -> &'static str {
+    let object = match input {
+        Value::Array(_) => return "bare ABI",
+        Value::Object(object) => object,
+        _ => return "unknown",
+    };
+
+    let is_hardhat = object
+        .get("_format")
+        .and_then(Value::as_str)
+        .map_or(false, |format| format.starts_with("hh-sol-artifact"));
+
+    if object.contains_key("contracts") {
+        "solc combined JSON"
+    } else if object.contains_key("output") && object.contains_key("settings") {
+        "solc metadata"
+    } else if is_hardhat {
+        "Hardhat artifact"
+    } else if object
+        .get("deployedBytecode")
+        .map_or(false, Value::is_object)
+    {
+        "Foundry artifact"
+    } else if object.contains_key("contractName") {
+        "Truffle artifact"
+    } else {
+        "unknown"
+    }
+}
+
+/// Name of the contract, if the input records it
+fn contract_name(input: &Value, contract: Option<&str>) -> Option<String> {
+    let compilation_target = |metadata: &Value| {
+        metadata["settings"]["compilationTarget"]
+            .as_object()?
+            .values()
+            .next()?
+            .as_str()
+            .map(str::to_owned)
+    };
+
+    // Combined JSON names contracts `<path>:<name>`
+    let combined = || match contract {
+        Some(contract) => contract.rsplit(':').next().map(str::to_owned),
+        None => input["contracts"]
+            .as_object()
+            .filter(|contracts| contracts.len() == 1)?
+            .keys()
+            .next()?
+            .rsplit(':')
+            .next()
+            .map(str::to_owned),
+    };
+
+    input["contractName"]
+        .as_str()
+        .map(str::to_owned)
+        .or_else(|| compilation_target(input))
+        .or_else(|| compilation_target(&input["metadata"]))
+        .or_else(combined)
+}
+
+/// Types of the parameter and its components the generator cannot parse
+fn unsupported_types(params: &JsonValue, found: &mut BTreeSet<String>) {
+    for param in params.members() {
+        let ty = canonical_type(param);
+        if ty.ends_with(" storage") || ethabi::param_type::Reader::read(&ty).is_err() {
+            found.insert(ty);
+        }
+    }
+}
+
+/// Summarizes the input: its format, contract, ABI entries, types
+/// Sumi does not support and the targets a module could be generated for
+pub fn render(source: &[u8], contract: Option<&str>) -> Result<String, Error> {
+    let input: Value = serde_json::from_slice(source)?;
+    let abi = json::parse(&artifact::read_contract_abi(&mut &source[..], contract)?)?;
+    if !abi.is_array() {
+        return Err(Error::Metadata("ABI should be an array".to_owned()));
+    }
+
+    let count = |ty: &str| abi.members().filter(|item| item["type"] == ty).count();
+
+    let mut mutability = BTreeMap::new();
+    let mut unsupported = BTreeSet::new();
+    for function in abi.members().filter(|item| item["type"] == "function") {
+        *mutability
+            .entry(sol2ink::state_mutability(function).unwrap_or("unknown"))
+            .or_insert(0) += 1;
+
+        let mut found = BTreeSet::new();
+        unsupported_types(&function["inputs"], &mut found);
+        unsupported_types(&function["outputs"], &mut found);
+        unsupported.extend(
+            found
+                .into_iter()
+                .map(|ty| format!("`{ty}` in `{}`", signature(function))),
+        );
+    }
+
+    let special = ["constructor", "fallback", "receive"]
+        .into_iter()
+        .filter(|&ty| count(ty) > 0)
+        .collect_vec();
+
+    let options = Options {
+        module_name: "contract".to_owned(),
+        ..Default::default()
+    };
+
+    let mut targets = Vec::new();
+    for target in Target::value_variants() {
+        let name = target
+            .to_possible_value()
+            .map(|value| value.get_name().to_owned())
+            .unwrap_or_default();
+
+        let generator = Generator::new(target, &CustomTemplates::default())?;
+        let status = match generator.model(&abi, &options) {
+            Ok(model) => format!(
+                "compatible, {} messages, {} warnings",
+                model.message_count(),
+                model.warnings().len()
+            ),
+            Err(e) => format!("not compatible: {e}"),
+        };
+
+        targets.push(format!("  {name:16}  {status}"));
+    }
+
+    let lines = [
+        format!("Format:     {}", detect_format(&input)),
+        format!(
+            "Contract:   {}",
+            contract_name(&input, contract).unwrap_or_else(|| "unknown".to_owned())
+        ),
+        format!(
+            "Functions:  {} ({})",
+            count("function"),
+            mutability
+                .iter()
+                .map(|(mutability, count)| format!("{mutability} {count}"))
+                .join(", ")
+        ),
+        format!("Events:     {}", count("event")),
+        format!("Errors:     {}", count("error")),
+        format!(
+            "Special:    {}",
+            if special.is_empty() {
+                "none".to_owned()
+            } else {
+                special.join(", ")
+            }
+        ),
+        format!(
+            "Unsupported types: {}",
+            if unsupported.is_empty() {
+                "none".to_owned()
+            } else {
+                unsupported.iter().join(", ")
+            }
+        ),
+        "Targets:".to_owned(),
+    ];
+
+    Ok(lines.into_iter().chain(targets).join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats() {
+        let hardhat =
+            serde_json::json!({"_format": "hh-sol-artifact-1", "contractName": "Token", "abi": []});
+        let foundry = serde_json::json!({"abi": [], "deployedBytecode": {"object": "0x"},
+            "metadata": {"settings": {"compilationTarget": {"src/Vault.sol": "Vault"}}}});
+
+        assert_eq!(detect_format(&serde_json::json!([])), "bare ABI");
+        assert_eq!(detect_format(&hardhat), "Hardhat artifact");
+        assert_eq!(contract_name(&hardhat, None).as_deref(), Some("Token"));
+        assert_eq!(detect_format(&foundry), "Foundry artifact");
+        assert_eq!(contract_name(&foundry, None).as_deref(), Some("Vault"));
+    }
+}
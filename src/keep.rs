@@ -0,0 +1,177 @@
+use crate::error::Error;
+use std::{fs, io, io::Write, path::Path};
+
+const BEGIN: &str = "// sumi:keep begin";
+const END: &str = "// sumi:keep end";
+
+fn begin_marker(line: &str) -> Option<&str> {
+    line.trim().strip_prefix(BEGIN).map(str::trim)
+}
+
+fn is_end_marker(line: &str) -> bool {
+    line.trim().starts_with(END)
+}
+
+/// Manually written regions of a previously generated file.
+/// Contents between `// sumi:keep begin <name>` and `// sumi:keep end`
+/// markers are carried over verbatim when the file is regenerated.
+#[derive(Debug, Default)]
+pub struct Regions {
+    regions: Vec<(String, String)>,
+}
+
+impl Regions {
+    /// Loads regions from the existing output. Missing file has no regions.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::ReadInput {
+                path: path.to_owned(),
+                inner: e,
+            }),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut regions = Vec::new();
+        let mut current: Option<(String, String)> = None;
+
+        for (number, line) in text.split_inclusive('\n').enumerate() {
+            match current.as_mut() {
+                Some((name, content)) => {
+                    if begin_marker(line).is_some() {
+                        return Err(Error::KeepRegion(format!(
+                            "region '{name}' is not closed before line {}",
+                            number + 1
+                        )));
+                    }
+
+                    if is_end_marker(line) {
+                        regions.push(current.take().expect("checked above"));
+                    } else {
+                        content.push_str(line);
+                    }
+                }
+
+                None => {
+                    if let Some(name) = begin_marker(line) {
+                        current = Some((name.to_owned(), String::new()));
+                    }
+                }
+            }
+        }
+
+        if let Some((name, _)) = current {
+            return Err(Error::KeepRegion(format!("region '{name}' is not closed")));
+        }
+
+        Ok(Regions { regions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Replaces contents of the generated regions with the kept ones
+    pub fn merge(&self, generated: &str) -> String {
+        let mut output = String::with_capacity(generated.len());
+        let mut used = vec![false; self.regions.len()];
+        let mut skipping = false;
+
+        for line in generated.split_inclusive('\n') {
+            if skipping {
+                if !is_end_marker(line) {
+                    continue;
+                }
+
+                skipping = false;
+            } else if let Some(name) = begin_marker(line) {
+                if let Some(index) = self.regions.iter().position(|(n, _)| n == name) {
+                    output.push_str(line);
+                    output.push_str(&self.regions[index].1);
+                    used[index] = true;
+                    skipping = true;
+                    continue;
+                }
+            }
+
+            output.push_str(line);
+        }
+
+        // Regions that have no place in the regenerated output
+        // are appended to the end, so nothing is ever lost
+        for ((name, content), used) in self.regions.iter().zip(used) {
+            if !used {
+                eprintln!("warning: region '{name}' is not present in the generated output, appending it to the end");
+
+                if !output.ends_with('\n') {
+                    output.push('\n');
+                }
+
+                output.push_str(&format!("{BEGIN} {name}\n{content}{END}\n"));
+            }
+        }
+
+        output
+    }
+
+    /// Calls `render` and writes its output to the writer, carrying over kept regions if there are any
+    pub fn render<F>(&self, writer: &mut dyn Write, render: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+    {
+        if self.is_empty() {
+            return render(writer);
+        }
+
+        let mut buffer = Vec::new();
+        render(&mut buffer)?;
+
+        writer.write_all(self.merge(&String::from_utf8_lossy(&buffer)).as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carries_regions_over() {
+        let existing = "\
+mod erc20 {
+    // sumi:keep begin module
+    fn helper() {}
+    // sumi:keep end
+}
+";
+
+        let generated = "\
+mod erc20 {
+    fn transfer() {}
+    // sumi:keep begin module
+    // sumi:keep end
+}
+";
+
+        let regions = Regions::parse(existing).unwrap();
+        assert_eq!(
+            regions.merge(generated),
+            "\
+mod erc20 {
+    fn transfer() {}
+    // sumi:keep begin module
+    fn helper() {}
+    // sumi:keep end
+}
+"
+        );
+    }
+
+    #[test]
+    fn rejects_unterminated_regions() {
+        assert!(Regions::parse("// sumi:keep begin a\n// sumi:keep begin b\n").is_err());
+        assert!(Regions::parse("// sumi:keep begin a\nfn helper() {}\n").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! Generates ink! wrappers of EVM contracts from their ABIs. The `sumi`
+//! command line tool is a thin layer over this crate, so build scripts
+//! and other tools could generate wrappers the same way:
+//!
+//! ```ignore
+//! let abi = sumi::parse_abi(&std::fs::read_to_string("abis/erc20.json")?)?;
+//! let options = sumi::GeneratorOptions {
+//!     module_name: "erc20".to_owned(),
+//!     evm_id: "0x0F".to_owned(),
+//!     ..Default::default()
+//! };
+//!
+//! let code = sumi::generate(&abi, &sumi::Target::AstarXvm, &options)?;
+//! ```
+//!
+//! Use [`Generator`] directly to inspect the [`Model`] and its warnings before rendering.
+
+mod append;
+pub mod artifact;
+pub mod batch;
+pub mod build;
+mod cache;
+pub mod calldata;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod deps;
+pub mod diff;
+pub mod docs;
+pub mod doctor;
+pub mod error;
+pub mod ethers;
+pub mod extension;
+pub mod fetch;
+pub mod filter;
+pub mod gas;
+pub mod hash;
+pub mod human;
+pub mod ident;
+pub mod init;
+pub mod ink2ink;
+pub mod ink2sol;
+pub mod input;
+pub mod inspect;
+pub mod keep;
+pub mod lint;
+pub mod list;
+pub mod manifest;
+pub mod migration;
+pub mod mock;
+pub mod normalize;
+pub mod pack;
+pub mod rpc;
+pub mod scaffold;
+pub mod scripts;
+pub mod selector_map;
+pub mod sol2ink;
+pub mod stamp;
+pub mod warning;
+
+pub use cli::Target;
+pub use error::Error;
+pub use sol2ink::{
+    CustomTemplates, Function, Generator, Model, Module, Options as GeneratorOptions,
+};
+pub use warning::{SkipReason, Warning};
+
+/// Parses ABI of any supported input: bare ABI, Hardhat, Foundry or
+/// Truffle artifact, solc metadata or combined JSON with a single contract
+pub fn parse_abi(source: &str) -> Result<json::JsonValue, Error> {
+    Ok(json::parse(&artifact::read_abi(&mut source.as_bytes())?)?)
+}
+
+/// Renders ink! module wrapping the contract with the built-in templates.
+/// Warnings are dropped, see [`Model::warnings`] to get them.
+pub fn generate(
+    abi: &json::JsonValue,
+    target: &Target,
+    options: &GeneratorOptions,
+) -> Result<String, Error> {
+    let generator = Generator::new(target, &CustomTemplates::default())?;
+    let model = generator.model(abi, options)?;
+
+    let mut code = Vec::new();
+    generator.render_to(&model, &mut code)?;
+    Ok(String::from_utf8(code).expect("templates render valid UTF-8"))
+}
@@ -0,0 +1,132 @@
+use crate::{
+    error::Error,
+    sol2ink::{selector, signature, MAX_ARGUMENTS},
+};
+use convert_case::{Case, Casing};
+use json::JsonValue;
+use std::collections::{HashMap, HashSet};
+
+/// Admin functions of EIP-1967 transparent proxies. Calls with
+/// their selectors are handled by the proxy, not forwarded.
+const PROXY_FUNCTIONS: &[&str] = &[
+    "admin()",
+    "implementation()",
+    "changeAdmin(address)",
+    "upgradeTo(address)",
+    "upgradeToAndCall(address,bytes)",
+];
+
+const MUTABILITIES: &[&str] = &["pure", "view", "nonpayable", "payable"];
+
+/// Finds patterns of the ABI that generate broken or surprising wrappers
+pub fn lint(abi: &JsonValue) -> Result<Vec<String>, Error> {
+    if !abi.is_array() {
+        return Err(Error::Metadata("ABI should be an array".to_owned()));
+    }
+
+    let proxy_selectors: HashMap<_, _> = PROXY_FUNCTIONS
+        .iter()
+        .map(|signature| (selector(signature), *signature))
+        .collect();
+
+    let mut findings = Vec::new();
+    let mut signatures = HashSet::new();
+    let mut messages = HashMap::new();
+
+    for function in abi.members().filter(|item| item["type"] == "function") {
+        let name = function["name"].as_str().unwrap_or_default();
+        let signature = signature(function);
+
+        if !signatures.insert(signature.clone()) {
+            findings.push(format!("`{signature}` is declared more than once"));
+        }
+
+        // Overloads share the message, but different names must not
+        let message = name.to_case(Case::Snake);
+        let previous = messages.insert(message.clone(), name);
+        if let Some(other) = previous.filter(|&other| other != name) {
+            findings.push(format!(
+                "`{other}` and `{name}` both generate message `{message}`"
+            ));
+        }
+
+        for (index, input) in function["inputs"].members().enumerate() {
+            if input["name"].as_str().map_or(true, str::is_empty) {
+                findings.push(format!(
+                    "parameter {index} of `{signature}` is unnamed and cannot become a message argument"
+                ));
+            }
+        }
+
+        // Missing mutability is taken care of by `missing_mutability` of the config
+        let mutability = function["stateMutability"].as_str().unwrap_or("nonpayable");
+        if !MUTABILITIES.contains(&mutability) {
+            findings.push(format!(
+                "`{signature}` has non-standard state mutability `{mutability}`"
+            ));
+        }
+
+        let arguments = function["inputs"].len();
+        if arguments > MAX_ARGUMENTS {
+            findings.push(format!(
+                "`{signature}` takes {arguments} arguments, more than the {MAX_ARGUMENTS} a message can take, so the message takes them packed into a struct"
+            ));
+        }
+
+        match proxy_selectors.get(&selector(&signature)) {
+            Some(&proxy) if proxy == signature => findings.push(format!(
+                "`{signature}` is an admin function of transparent proxies, which do not forward it"
+            )),
+            Some(proxy) => findings.push(format!(
+                "selector of `{signature}` collides with `{proxy}` of transparent proxies"
+            )),
+            None => {}
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Prints the findings, failing if there are any
+pub fn run(abi: &JsonValue) -> Result<(), Error> {
+    let findings = lint(abi)?;
+    for finding in &findings {
+        println!("warning: {finding}");
+    }
+
+    match findings.len() {
+        0 => Ok(()),
+        count => Err(Error::Lint(count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_suspicious_patterns() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "setURI", "inputs": [{"name": "", "type": "string"}]},
+                {"type": "function", "name": "setUri", "inputs": [{"name": "uri", "type": "string"}],
+                 "stateMutability": "constant"},
+                {"type": "function", "name": "upgradeTo", "inputs": [{"name": "to", "type": "address"}]},
+                {"type": "function", "name": "transfer", "inputs": [
+                    {"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "stateMutability": "nonpayable"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            lint(&abi).unwrap(),
+            [
+                "parameter 0 of `setURI(string)` is unnamed and cannot become a message argument",
+                "`setURI` and `setUri` both generate message `set_uri`",
+                "`setUri(string)` has non-standard state mutability `constant`",
+                "`upgradeTo(address)` is an admin function of transparent proxies, which do not forward it",
+            ]
+        );
+    }
+}
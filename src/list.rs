@@ -0,0 +1,161 @@
+use crate::{
+    error::Error,
+    filter::Filter,
+    sol2ink::{self, canonical_type, signature},
+};
+use hex::ToHex;
+use itertools::Itertools;
+use json::JsonValue;
+use std::collections::HashSet;
+
+/// Parameters in `type name` form
+fn params(params: &JsonValue) -> String {
+    params
+        .members()
+        .map(|param| match param["name"].as_str() {
+            Some(name) if !name.is_empty() => format!("{} {name}", canonical_type(param)),
+            _ => canonical_type(param),
+        })
+        .join(", ")
+}
+
+/// Renders rows as columns padded to the widest cell
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths = header.iter().map(|cell| cell.len()).collect_vec();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let header = header.iter().map(|cell| cell.to_string()).collect_vec();
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        })
+        .join("\n")
+}
+
+/// Renders table of functions and events of the ABI. Functions that are not
+/// wrapped with the given filter (views, functions returning values, etc.)
+/// are marked, so the table shows exactly what the wrapper will expose.
+pub fn render(abi: &JsonValue, filter: &Filter) -> Result<String, Error> {
+    if !abi.is_array() {
+        return Err(Error::Metadata("ABI should be an array".to_owned()));
+    }
+
+    let wrapped: HashSet<usize> = sol2ink::functions(abi, filter, Default::default(), false)
+        .map(|(index, _)| index)
+        .collect();
+
+    let functions = abi
+        .members()
+        .enumerate()
+        .filter(|(_, item)| item["type"] == "function")
+        .map(|(index, item)| {
+            let signature = signature(item);
+            vec![
+                item["name"].as_str().unwrap_or_default().to_owned(),
+                sol2ink::state_mutability(item)
+                    .unwrap_or("unknown")
+                    .to_owned(),
+                params(&item["inputs"]),
+                params(&item["outputs"]),
+                format!("0x{}", sol2ink::selector(&signature).encode_hex::<String>()),
+                if wrapped.contains(&index) {
+                    "yes"
+                } else {
+                    "no"
+                }
+                .to_owned(),
+            ]
+        })
+        .collect();
+
+    let events = abi
+        .members()
+        .filter(|item| item["type"] == "event")
+        .map(|item| {
+            vec![
+                item["name"].as_str().unwrap_or_default().to_owned(),
+                params(&item["inputs"]),
+                format!(
+                    "0x{}",
+                    sol2ink::topic(&signature(item)).encode_hex::<String>()
+                ),
+            ]
+        })
+        .collect_vec();
+
+    let mut output = table(
+        &[
+            "FUNCTION",
+            "MUTABILITY",
+            "INPUTS",
+            "OUTPUTS",
+            "SELECTOR",
+            "WRAPPED",
+        ],
+        functions,
+    );
+
+    if !events.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&table(&["EVENT", "INPUTS", "TOPIC"], events));
+    }
+
+    Ok(output)
+}
+
+/// Renders table of function signatures and their selectors, followed by custom errors
+pub fn selectors(abi: &JsonValue) -> Result<String, Error> {
+    if !abi.is_array() {
+        return Err(Error::Metadata("ABI should be an array".to_owned()));
+    }
+
+    let rows = |kind| {
+        abi.members()
+            .filter(|item| item["type"] == kind)
+            .map(|item| {
+                let signature = signature(item);
+                vec![
+                    format!("0x{}", sol2ink::selector(&signature).encode_hex::<String>()),
+                    signature,
+                ]
+            })
+            .collect_vec()
+    };
+
+    let mut output = table(&["SELECTOR", "FUNCTION"], rows("function"));
+    let errors = rows("error");
+    if !errors.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&table(&["SELECTOR", "ERROR"], errors));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_tuple_types() {
+        let param = json::parse(
+            r#"{"type": "tuple[]", "components": [
+                {"type": "address"},
+                {"type": "tuple", "components": [{"type": "uint256"}, {"type": "bool"}]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(canonical_type(&param), "(address,(uint256,bool))[]");
+    }
+}
@@ -1,56 +1,1162 @@
-mod cli;
-mod error;
-mod ink2sol;
-mod sol2ink;
+use sumi::{
+    artifact, batch, calldata, cli, client, config, deps, diff, docs, doctor, error, ethers,
+    extension, fetch, filter, gas, human, ident, init, ink2ink, ink2sol, input, inspect, keep,
+    lint, list, manifest, migration, mock, normalize, pack, rpc, scaffold, scripts, selector_map,
+    sol2ink, stamp, warning,
+};
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 use error::Error;
 use std::{
     fs,
-    io::{self, BufRead, BufReader, BufWriter, Write},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
-fn main() -> anyhow::Result<()> {
-    let args = cli::Args::parse();
+/// Reads input source, extracting ABI of the contract from build artifacts when needed
+fn load(
+    mode: &cli::Mode,
+    contract: Option<&str>,
+    reader: &mut dyn BufRead,
+) -> Result<String, Error> {
+    match mode {
+        cli::Mode::EvmToInk => artifact::read_contract_abi(reader, contract),
+        cli::Mode::InkToEvm | cli::Mode::InkToInk => {
+            let mut buffer = String::new();
+            reader.read_to_string(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+}
 
-    let mut reader: Box<dyn BufRead> = match args.input {
-        Some(filename) => Box::new(BufReader::new(fs::File::open(&filename).map_err(|e| {
-            Error::ReadInput {
-                path: filename,
-                inner: e,
+/// Writes additional artifacts requested with `--emit`
+fn emit(
+    args: &cli::Args,
+    json: &json::JsonValue,
+    model: &sol2ink::Model,
+    module_name: &str,
+) -> Result<(), Error> {
+    let dir = args
+        .emit_dir
+        .clone()
+        .or_else(|| args.output_dir.clone())
+        .or_else(|| args.output.as_ref()?.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+
+    for kind in &args.emit {
+        let files = match kind {
+            cli::Emit::SolidityMock => vec![(
+                PathBuf::from(format!("{}.sol", mock::contract_name(module_name))),
+                mock::render(json, module_name)?,
+            )],
+
+            cli::Emit::ChainExtension => vec![(
+                PathBuf::from(extension::file_name(module_name)),
+                extension::render(model, module_name)?,
+            )],
+
+            cli::Emit::EthersClient => vec![(
+                PathBuf::from(ethers::file_name(module_name)),
+                ethers::render(json, module_name)?,
+            )],
+
+            cli::Emit::CallScripts => vec![(
+                scripts::path(module_name),
+                scripts::render(model, module_name)?,
+            )],
+
+            cli::Emit::SubxtClient => {
+                let crate_dir = PathBuf::from(client::crate_name(module_name));
+                client::render(model, module_name)?
+                    .into_iter()
+                    .map(|(path, contents)| (crate_dir.join(path), contents))
+                    .collect()
             }
-        })?)),
-        None => Box::new(BufReader::new(io::stdin())),
+        };
+
+        for (file_name, contents) in files {
+            let path = dir.join(file_name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| Error::WriteOutput {
+                    path: parent.to_path_buf(),
+                    inner: e,
+                })?;
+            }
+
+            fs::write(&path, contents).map_err(|e| Error::WriteOutput { path, inner: e })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Inputs of generation loaded once and shared by all modules
+struct Context {
+    config: config::Config,
+    gas_report: Option<Arc<gas::Report>>,
+    docs: Option<Arc<docs::Docs>>,
+    templates: sol2ink::CustomTemplates,
+
+    /// Integrity of the resolved template pack, which changes with any of its files
+    pack_integrity: Option<String>,
+
+    ink: sol2ink::InkCrates,
+}
+
+/// Options of module generation common to all modules
+fn module_options(
+    args: &cli::Args,
+    context: &Context,
+    module_name: &str,
+    namespace: Option<String>,
+) -> sol2ink::Options {
+    sol2ink::Options {
+        module_name: module_name.to_owned(),
+        evm_id: args.evm_id.clone(),
+        para_id: args.para_id,
+        filter: context.config.filter(&args.only, &args.exclude),
+        namespace,
+        gas_report: context.gas_report.clone(),
+        docs: context.docs.clone(),
+        features: context.config.feature_filters(),
+        target_groups: context.config.target_groups(),
+        imports: imports(args, context),
+        attributes: context.config.module_attributes(),
+        storage: context
+            .config
+            .storage(matches!(args.storage, cli::StorageLayout::Lazy)),
+        caller: context.config.caller(),
+        missing_mutability: context.config.abi.missing_mutability,
+        selector_table: args.selector_table,
+        include_views: args.include_views,
+        call_defaults: context.config.call_defaults(),
+        allow_empty: args.allow_empty,
+        signature_helpers: args.signature_helpers,
+        native_types: args.native_types.then(|| context.config.account_mapping()),
+        overloads: args.overloads.unwrap_or(context.config.abi.overloads),
+        trait_definition: args.trait_definition,
+        typed_errors: args.typed_errors,
+        psp22: matches!(args.adapter, Some(cli::Adapter::Psp22))
+            .then(|| context.config.account_mapping()),
+        psp34: matches!(args.adapter, Some(cli::Adapter::Psp34))
+            .then(|| context.config.account_mapping()),
+        xvm_version: args.xvm_version,
+        gas_limit_setter: args.gas_limit_setter,
+        ink: context.ink.clone(),
+        shared_types: args.workspace.as_ref().map(|_| deps::TYPES.name.to_owned()),
+        type_overrides: context.config.type_overrides(),
+    }
+}
+
+/// Imports of the config followed by the command line ones
+fn imports(args: &cli::Args, context: &Context) -> sol2ink::Imports {
+    let config = &context.config.imports;
+    let default = config.default && !args.no_default_imports;
+
+    // Both `path` and `use path;` forms are accepted
+    let extra = config
+        .extra
+        .iter()
+        .chain(&args.extra_use)
+        .map(|path| {
+            let path = path.trim().trim_end_matches(';');
+            path.strip_prefix("use ").unwrap_or(path).trim().to_owned()
+        })
+        // Importing the same path twice does not compile
+        .filter(|path| !default || !sol2ink::DEFAULT_IMPORTS.contains(&path.as_str()))
+        .collect();
+
+    sol2ink::Imports { default, extra }
+}
+
+/// Summary of everything affecting the generated code besides the input, so
+/// batch outputs are regenerated when it changes. Arguments only affecting
+/// how the batch runs are left out, changing them keeps the outputs fresh.
+fn fingerprint(args: &cli::Args, context: &Context) -> String {
+    let args = cli::Args {
+        jobs: None,
+        no_cache: false,
+        prune: false,
+        ..args.clone()
     };
 
-    let mut writer: Box<dyn Write> = match args.output {
-        Some(filename) => Box::new(BufWriter::new(fs::File::create(&filename).map_err(
-            |e| Error::WriteOutput {
-                path: filename,
+    format!(
+        "{args:?} {:?} {:?} {:?} {} {:?}",
+        context.config,
+        context.gas_report,
+        context.docs,
+        context.templates.fingerprint(),
+        context.pack_integrity
+    )
+}
+
+/// Renders the source to the writer. Generator is created
+/// on first use and then reused for all subsequent calls.
+fn render(
+    args: &cli::Args,
+    context: &Context,
+    generator: &mut Option<sol2ink::Generator<'static>>,
+    source: &str,
+    module_name: Option<&str>,
+    writer: &mut dyn Write,
+) -> Result<(), Error> {
+    match args.mode {
+        cli::Mode::EvmToInk => {
+            let parsed_json = json::parse(source)?;
+            let module_name = module_name.ok_or(Error::MissingModuleName)?;
+
+            if generator.is_none() {
+                *generator = Some(sol2ink::Generator::new(&args.target, &context.templates)?);
+            }
+
+            let options = module_options(args, context, module_name, None);
+
+            let generator = generator.as_ref().expect("initialized above");
+            let model = generator.model(&parsed_json, &options)?;
+            report(&model, args.strict)?;
+
+            emit(args, &parsed_json, &model, module_name)?;
+            generator.render_to(&model, writer)?;
+
+            if args.tests {
+                generator.render_tests_to(&model, writer)?;
+            }
+
+            if args.with_e2e_tests {
+                generator.render_e2e_tests_to(&model, writer)?;
+            }
+        }
+
+        cli::Mode::InkToEvm => {
+            let rendered =
+                ink2sol::render(&mut source.as_bytes(), &module_name.map(str::to_owned))?;
+            writer.write_all(rendered.as_bytes())?;
+        }
+
+        cli::Mode::InkToInk => {
+            writer.write_all(ink2ink::render(source, module_name)?.as_bytes())?;
+        }
+    }
+
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Prints warnings of the model followed by the summary of the skipped functions.
+/// Read-only functions are skipped by default, so they are only counted in the
+/// summary. In strict mode any skipped function fails the generation instead.
+fn report(model: &sol2ink::Model, strict: bool) -> Result<(), Error> {
+    let warnings = model.warnings();
+    if strict {
+        warning::strict(warnings)?;
+    }
+
+    for warning in warnings {
+        if !matches!(
+            warning,
+            warning::Warning::SkippedFunction {
+                reason: warning::SkipReason::ReadOnly,
+                ..
+            }
+        ) {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    if let Some(summary) = warning::skip_summary(warnings) {
+        eprintln!("note: {summary}, pass --strict to fail instead");
+    }
+
+    Ok(())
+}
+
+/// Renders router wrapping every fetched contract or every input of the directory
+fn route(
+    args: &cli::Args,
+    context: &Context,
+    router: &str,
+    fetched: Vec<fetch::Fetched>,
+    writer: &mut dyn Write,
+) -> Result<(), Error> {
+    let sources = match &args.input_dir {
+        Some(input_dir) => {
+            let inputs = batch::collect_inputs(input_dir)?;
+            batch::ensure_unique(
+                inputs
+                    .iter()
+                    .map(|path| (batch::module_name(path), path.display().to_string())),
+            )?;
+
+            inputs
+                .into_iter()
+                .map(|path| {
+                    let source = load(&args.mode, None, &mut &input::open(&path)?[..])?;
+                    Ok((batch::module_name(&path), source))
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        }
+
+        None => {
+            batch::ensure_unique(
+                fetched
+                    .iter()
+                    .map(|item| (item.module_name(), item.address.clone())),
+            )?;
+
+            fetched
+                .into_iter()
+                .map(|item| (item.module_name(), item.abi))
+                .collect()
+        }
+    };
+
+    let generator = sol2ink::Generator::new(&args.target, &context.templates)?;
+    let models = sources
+        .iter()
+        .map(|(namespace, source)| {
+            let options = module_options(args, context, router, Some(namespace.clone()));
+            let model = generator.model(&json::parse(source)?, &options)?;
+            report(&model, args.strict)?;
+            Ok(model)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let options = module_options(args, context, router, None);
+    generator.render_router_to(&options, &models, writer)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Inserts functions missing from the existing output instead of regenerating it
+fn append(
+    args: &cli::Args,
+    context: &Context,
+    source: &str,
+    module_name: Option<&str>,
+    output: &Path,
+) -> Result<(), Error> {
+    let existing = fs::read_to_string(output).map_err(|e| Error::ReadInput {
+        path: output.to_owned(),
+        inner: e,
+    })?;
+
+    let parsed_json = json::parse(source)?;
+    let module_name = module_name.ok_or(Error::MissingModuleName)?;
+    let generator = sol2ink::Generator::new(&args.target, &context.templates)?;
+    let model = generator.model(
+        &parsed_json,
+        &module_options(args, context, module_name, None),
+    )?;
+    report(&model, args.strict)?;
+    emit(args, &parsed_json, &model, module_name)?;
+
+    let (updated, added) = generator.append_to(&model, &existing)?;
+    if added.is_empty() {
+        eprintln!("{} is up to date", output.display());
+        return Ok(());
+    }
+
+    fs::write(output, updated).map_err(|e| Error::WriteOutput {
+        path: output.to_owned(),
+        inner: e,
+    })?;
+
+    for signature in added {
+        eprintln!("+ {signature}");
+    }
+
+    Ok(())
+}
+
+/// Writes single output to the file or stdout, preserving kept regions
+fn write_single<F>(args: &cli::Args, module_name: &str, render: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+{
+    // Manually written regions and previous messages
+    // have to be read before the output is truncated
+    let regions = match &args.output {
+        Some(filename) => keep::Regions::load(filename)?,
+        None => keep::Regions::default(),
+    };
+
+    if let Some(filename) = args.output.as_ref().filter(|_| args.check) {
+        return check(filename, &regions, render);
+    }
+
+    let snapshot = args
+        .output
+        .as_ref()
+        .map(|filename| migration::Snapshot::take(module_name, filename));
+
+    let mut writer: Box<dyn Write> = match &args.output {
+        Some(filename) => Box::new(BufWriter::new(fs::File::create(filename).map_err(|e| {
+            Error::WriteOutput {
+                path: filename.clone(),
                 inner: e,
-            },
-        )?)),
+            }
+        })?)),
         None => Box::new(BufWriter::new(io::stdout())),
     };
 
-    let rendered = match args.mode {
-        cli::Mode::EvmToInk => {
-            let parsed_json = {
-                let mut buffer = String::new();
-                reader.read_to_string(&mut buffer)?;
+    regions.render(&mut writer, render)?;
+    writer.flush()?;
+    drop(writer);
+
+    if let (Some(snapshot), Some(filename)) = (snapshot, &args.output) {
+        if let Some(notes) = snapshot.notes(filename) {
+            let dir = filename.parent().unwrap_or_else(|| Path::new(""));
+            migration::write(dir, &[notes])?;
+        }
+    }
 
-                json::parse(&buffer).map_err(Error::from)?
+    Ok(())
+}
+
+/// Compares the regenerated output with the file, printing the diff if they differ
+fn check<F>(filename: &Path, regions: &keep::Regions, render: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+{
+    let mut regenerated = Vec::new();
+    regions.render(&mut regenerated, render)?;
+
+    // Missing output is as outdated as it gets
+    let existing = fs::read_to_string(filename).unwrap_or_default();
+    let name = filename.display().to_string();
+    match diff::unified(
+        &existing,
+        &String::from_utf8_lossy(&regenerated),
+        &name,
+        &format!("{name} (regenerated)"),
+    ) {
+        Some(diff) => {
+            print!("{diff}");
+            Err(Error::OutdatedOutput(filename.to_owned()))
+        }
+        None => Ok(()),
+    }
+}
+
+fn fetch_options(args: &cli::Args) -> fetch::Options {
+    let source = match (&args.sourcify, &args.explorer_url) {
+        (Some(chain_id), _) => fetch::Source::Sourcify {
+            url: fetch::SOURCIFY_URL.to_owned(),
+            chain_id: *chain_id,
+        },
+
+        (None, Some(url)) => fetch::Source::Explorer {
+            url: url.clone(),
+            api_key: args.api_key.clone(),
+        },
+
+        (None, None) => cli::Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--fetch requires either --explorer-url or --sourcify",
+            )
+            .exit(),
+    };
+
+    fetch::Options {
+        source,
+        concurrency: args.jobs.unwrap_or(4),
+        rate_limit: args.rate_limit,
+        retries: args.retries,
+        cache_dir: (!args.no_cache).then(|| args.fetch_cache.clone()),
+    }
+}
+
+/// Reads ABI of the contract from the file or stdin
+fn read_abi_source(path: Option<&Path>, contract: Option<&str>) -> Result<String, Error> {
+    match path {
+        Some(path) => artifact::read_contract_abi(&mut &input::open(path)?[..], contract),
+        None => artifact::read_contract_abi(&mut BufReader::new(io::stdin()), contract),
+    }
+}
+
+fn read_abi(path: Option<&Path>, contract: Option<&str>) -> Result<serde_json::Value, Error> {
+    Ok(serde_json::from_str(&read_abi_source(path, contract)?)?)
+}
+
+/// Runs utility subcommand
+fn run(command: &cli::Command) -> Result<(), Error> {
+    match command {
+        cli::Command::Generate(_) => unreachable!("generation is not a utility subcommand"),
+
+        cli::Command::Selectors { input, contract } => {
+            let abi = json::parse(&read_abi_source(input.as_deref(), contract.as_deref())?)?;
+            println!("{}", list::selectors(&abi)?);
+        }
+
+        cli::Command::Encode {
+            input,
+            contract,
+            function,
+            args,
+        } => {
+            // The signature alone is enough to encode the call
+            let source = match input {
+                None if function.contains('(') => human::parse(function)?,
+                _ => read_abi_source(input.as_deref(), contract.as_deref())?,
             };
 
-            sol2ink::render(parsed_json, &args.module_name.unwrap(), &args.evm_id)?
+            println!(
+                "{}",
+                calldata::encode(&json::parse(&source)?, function, args)?
+            );
         }
 
-        cli::Mode::InkToEvm => {
-            ink2sol::render(&mut reader, &args.module_name)?
+        cli::Command::Decode {
+            input,
+            contract,
+            function,
+            data,
+        } => {
+            let abi = json::parse(&read_abi_source(input.as_deref(), contract.as_deref())?)?;
+            println!("{}", calldata::decode(&abi, data, function.as_deref())?);
+        }
+
+        cli::Command::Abi { input, contract } => {
+            let abi = read_abi(input.as_deref(), contract.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&abi)?);
+        }
+
+        cli::Command::List {
+            input,
+            contract,
+            only,
+            exclude,
+        } => {
+            let abi = json::parse(&read_abi_source(input.as_deref(), contract.as_deref())?)?;
+            let filter = filter::Filter::new(only.clone()).excluding(exclude.clone());
+            println!("{}", list::render(&abi, &filter)?);
+        }
+
+        cli::Command::Inspect { input, contract } => {
+            let summary = match input {
+                Some(path) => inspect::render(&input::open(path)?, contract.as_deref())?,
+                None => {
+                    let mut source = Vec::new();
+                    io::stdin().read_to_end(&mut source)?;
+                    inspect::render(&source, contract.as_deref())?
+                }
+            };
+
+            println!("{summary}");
+        }
+
+        cli::Command::Lint { input, contract } => {
+            lint::run(&json::parse(&read_abi_source(
+                input.as_deref(),
+                contract.as_deref(),
+            )?)?)?;
+        }
+
+        cli::Command::Check {
+            input,
+            contract,
+            module,
+        } => {
+            let abi = read_abi(input.as_deref(), contract.as_deref())?;
+            let text = fs::read_to_string(module).map_err(|e| Error::ReadInput {
+                path: module.clone(),
+                inner: e,
+            })?;
+
+            stamp::check(abi, module, &text)?;
+            eprintln!("{} is up to date", module.display());
+        }
+
+        cli::Command::FmtAbi {
+            input,
+            output,
+            contract,
+        } => {
+            // Input is read completely before the output is created, so both may be the same file
+            let abi = read_abi(input.as_deref(), contract.as_deref())?;
+            let formatted = serde_json::to_string_pretty(&normalize::normalize(abi)?)? + "\n";
+
+            match output {
+                Some(path) => fs::write(path, formatted).map_err(|e| Error::WriteOutput {
+                    path: path.clone(),
+                    inner: e,
+                })?,
+                None => io::stdout().write_all(formatted.as_bytes())?,
+            }
+        }
+
+        cli::Command::Doctor {
+            manifest,
+            mode,
+            target,
+            xvm_version,
+        } => doctor::run(manifest, mode, target, *xvm_version)?,
+
+        cli::Command::SelectorMap {
+            inputs,
+            input_dir,
+            output,
+            format,
+            only,
+            exclude,
+            include_views,
+        } => {
+            let mut paths = inputs.clone();
+            if let Some(input_dir) = input_dir {
+                paths.extend(batch::collect_inputs(input_dir)?);
+            }
+
+            let filter = filter::Filter::new(only.clone()).excluding(exclude.clone());
+            let mut entries = Vec::new();
+            for path in &paths {
+                let abi = json::parse(&read_abi_source(Some(path), None)?)?;
+                entries.extend(selector_map::entries(
+                    &batch::module_name(path),
+                    &abi,
+                    &filter,
+                    *include_views,
+                )?);
+            }
+
+            let rendered = match format {
+                cli::MapFormat::Json => serde_json::to_string_pretty(&entries)? + "\n",
+                cli::MapFormat::Csv => selector_map::to_csv(&entries),
+            };
+
+            match output {
+                Some(path) => fs::write(path, rendered).map_err(|e| Error::WriteOutput {
+                    path: path.clone(),
+                    inner: e,
+                })?,
+                None => io::stdout().write_all(rendered.as_bytes())?,
+            }
+        }
+
+        cli::Command::Init { dir, force } => {
+            for path in init::run(dir, *force)? {
+                eprintln!("created {}", path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = cli::Cli::parse();
+    let mut args = match cli.command {
+        None => cli.args,
+        Some(cli::Command::Generate(args)) => *args,
+        Some(command) => {
+            run(&command)?;
+            return Ok(());
+        }
+    };
+
+    let versions = deps::Versions {
+        ink: args.ink_version.clone(),
+        ethabi: args.ethabi_version.clone(),
+        substrate_branch: args.substrate_branch.clone(),
+    };
+    versions.validate()?;
+
+    if args.emit_deps {
+        let dependencies = deps::of(&args.mode, &args.target, args.xvm_version, &versions);
+        print!("{}", deps::render(&dependencies, &versions)?);
+        return Ok(());
+    }
+
+    if let Some(manifest) = &args.write_deps {
+        for name in deps::write(
+            manifest,
+            &deps::of(&args.mode, &args.target, args.xvm_version, &versions),
+            &versions,
+        )? {
+            eprintln!("+ {name}");
+        }
+
+        return Ok(());
+    }
+
+    if let Some(dir) = &args.scaffold {
+        if !matches!(args.mode, cli::Mode::EvmToInk)
+            || matches!(args.target, cli::Target::FrontierRuntime)
+        {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--scaffold is only supported in evm-to-ink mode for ink! targets",
+                )
+                .exit();
+        }
+
+        args.output = Some(dir.join(scaffold::LIB));
+    }
+
+    if let Some(dir) = &args.workspace {
+        if !matches!(args.mode, cli::Mode::EvmToInk)
+            || matches!(args.target, cli::Target::FrontierRuntime)
+        {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--workspace is only supported in evm-to-ink mode for ink! targets",
+                )
+                .exit();
+        }
+
+        args.output_dir = Some(dir.clone());
+    }
+
+    if args.output_dir.is_some() && args.input_dir.is_none() && args.fetch.len() < 2 {
+        cli::Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--output-dir requires --input-dir or several --fetch addresses",
+            )
+            .exit();
+    }
+
+    if args.input_dir.is_some() && args.output_dir.is_none() && args.router.is_none() {
+        cli::Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--input-dir requires --output-dir or --router",
+            )
+            .exit();
+    }
+
+    // Other modes name the generated contract after the module, not a Rust module
+    if let (cli::Mode::EvmToInk, Some(name)) = (&args.mode, &args.module_name) {
+        ident::validate_module_name(name)?;
+    }
+
+    let config = config::Config::discover(args.config.as_deref())?;
+    let mut templates =
+        sol2ink::CustomTemplates::load(args.template.as_deref(), args.template_dir.as_deref())?;
+
+    let mut pack_integrity = None;
+    if let Some(templates_pack) = &config.templates {
+        let (dir, integrity) = pack::resolve(templates_pack.source(), templates_pack.integrity())?;
+        if templates_pack.integrity().is_none() {
+            eprintln!(
+                "warning: template pack {} is not pinned, add `integrity = \"{integrity}\"` to the config",
+                templates_pack.source()
+            );
+        }
+
+        templates.extend(&dir)?;
+        pack_integrity = Some(integrity);
+    }
+
+    let context = Context {
+        config,
+        gas_report: match &args.gas_report {
+            Some(path) => Some(Arc::new(gas::Report::load(path)?)),
+            None => None,
+        },
+        docs: match &args.docs {
+            Some(path) => Some(Arc::new(docs::Docs::load(path)?)),
+            None => None,
         },
+        templates,
+        pack_integrity,
+        ink: versions
+            .ink_crates()
+            .expect("ink! version is validated before; cannot fail"),
     };
 
-    write!(writer, "{}\n", rendered)?;
+    let mut fetched = if let (Some(url), Some(address)) = (&args.rpc, &args.address) {
+        if !matches!(args.mode, cli::Mode::EvmToInk) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--rpc is only supported in evm-to-ink mode",
+                )
+                .exit();
+        }
+
+        vec![rpc::resolve(&rpc::Options {
+            url: url.clone(),
+            address: address.clone(),
+            registry: args.registry.clone(),
+            ipfs_gateway: args.ipfs_gateway.clone(),
+        })?]
+    } else if args.fetch.is_empty() {
+        Vec::new()
+    } else {
+        if !matches!(args.mode, cli::Mode::EvmToInk) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--fetch is only supported in evm-to-ink mode",
+                )
+                .exit();
+        }
+
+        fetch::fetch_all(&args.fetch, &fetch_options(&args))?
+    };
+
+    if args.tests && !matches!(args.target, cli::Target::AstarXvm) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--tests is only supported by the astar-xvm target",
+            )
+            .exit();
+    }
+
+    if args.typed_errors && !matches!(args.target, cli::Target::AstarXvm) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--typed-errors is only supported by the astar-xvm target",
+            )
+            .exit();
+    }
+
+    if args.include_views && !matches!(args.target, cli::Target::AstarXvm) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--include-views is only supported by the astar-xvm target",
+            )
+            .exit();
+    }
+
+    if args.xvm_version != sol2ink::XvmVersion::ChainExtension
+        && !matches!(args.target, cli::Target::AstarXvm)
+    {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--xvm-version is only supported by the astar-xvm target",
+            )
+            .exit();
+    }
+
+    if args.gas_limit_setter && !args.xvm_version.takes_gas_limit() {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--gas-limit-setter needs an XVM version taking a gas limit, i.e. --xvm-version v3",
+            )
+            .exit();
+    }
+
+    // `pallet-xvm` v2 does not return output of the call
+    if !args.xvm_version.returns_output() {
+        let needs_output = [
+            (args.include_views, "--include-views"),
+            (args.typed_errors, "--typed-errors"),
+            (args.adapter.is_some(), "--adapter"),
+        ];
+
+        if let Some((_, flag)) = needs_output.iter().find(|(given, _)| *given) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("--xvm-version v2 cannot be used with {flag}, which needs output of the calls"),
+                )
+                .exit();
+        }
+    }
+
+    if args.with_e2e_tests && (!context.ink.e2e || !matches!(args.target, cli::Target::AstarXvm)) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--with-e2e-tests needs --ink-version 4 or 5 and the astar-xvm target, ink_e2e is a part of ink! 4 and later",
+            )
+            .exit();
+    }
+
+    // Only the templates of the astar-xvm target are written for the `ink` crate as well
+    if !context.ink.separate {
+        let unsupported = [
+            (
+                !matches!(args.mode, cli::Mode::EvmToInk),
+                "modes other than evm-to-ink",
+            ),
+            (
+                matches!(args.target, cli::Target::MoonbeamXcm),
+                "the moonbeam-xcm target",
+            ),
+            (
+                matches!(args.storage, cli::StorageLayout::Lazy),
+                "--storage lazy",
+            ),
+        ];
+
+        if let Some((_, what)) = unsupported.iter().find(|(conflicts, _)| *conflicts) {
+            let major = versions.ink_major().unwrap_or_default();
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("ink! {major} code cannot be generated for {what}"),
+                )
+                .exit();
+        }
+    }
+
+    if matches!(args.storage, cli::StorageLayout::Lazy)
+        && matches!(args.target, cli::Target::FrontierRuntime)
+    {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--storage lazy is not supported by the frontier-runtime target",
+            )
+            .exit();
+    }
+
+    let has_default_value = context
+        .config
+        .functions
+        .values()
+        .any(|function| function.default_value.is_some());
+
+    if has_default_value && matches!(args.target, cli::Target::AstarXvm) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "default_value of the config is not supported by the astar-xvm target, XVM calls carry no value",
+            )
+            .exit();
+    }
+
+    if context.config.caller.is_some() && matches!(args.target, cli::Target::FrontierRuntime) {
+        cli::Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "caller forwarding of the config is not supported by the frontier-runtime target",
+            )
+            .exit();
+    }
+
+    if let Some(router) = &args.router {
+        if !matches!(args.mode, cli::Mode::EvmToInk) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--router is only supported in evm-to-ink mode",
+                )
+                .exit();
+        }
+
+        if matches!(args.target, cli::Target::FrontierRuntime) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--router is not supported by the frontier-runtime target",
+                )
+                .exit();
+        }
+
+        if args.input_dir.is_none() && fetched.is_empty() {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "--router requires --input-dir or --fetch",
+                )
+                .exit();
+        }
+
+        write_single(&args, router, |writer| {
+            route(&args, &context, router, fetched, writer)
+        })?;
+        return Ok(());
+    }
+
+    if let Some(output_dir) = args.output_dir.as_ref().filter(|_| fetched.len() > 1) {
+        fs::create_dir_all(output_dir).map_err(|e| Error::WriteOutput {
+            path: output_dir.clone(),
+            inner: e,
+        })?;
+
+        batch::ensure_unique(
+            fetched
+                .iter()
+                .map(|item| (item.module_name(), item.address.clone())),
+        )?;
+
+        let mut generator = None;
+        let mut notes = Vec::new();
+        let mut module_names = Vec::new();
+        let mut outputs = Vec::new();
+        for item in fetched {
+            let module_name = item.module_name();
+            let pattern = context.config.output.path.as_deref();
+            let output = batch::output_path(output_dir, pattern, &module_name, "rs");
+            let snapshot = migration::Snapshot::take(&module_name, &output);
+            batch::create_parent(&output)?;
+
+            batch::write_output(&output, |writer| {
+                render(
+                    &args,
+                    &context,
+                    &mut generator,
+                    &item.abi,
+                    Some(&module_name),
+                    writer,
+                )
+            })?;
+
+            eprintln!("{} -> {}", item.address, output.display());
+            notes.extend(snapshot.notes(&output));
+            module_names.push(module_name);
+            outputs.push((output, item.address));
+        }
+
+        if !notes.is_empty() {
+            migration::write(output_dir, &notes)?;
+        }
+
+        if context.config.output.path.is_none() {
+            batch::write_mod_file(output_dir, &module_names)?;
+        }
+
+        manifest::update(output_dir, outputs, args.prune)?;
+
+        return Ok(());
+    }
+
+    if let (Some(input_dir), Some(output_dir)) = (&args.input_dir, &args.output_dir) {
+        let extension = match args.mode {
+            cli::Mode::EvmToInk | cli::Mode::InkToInk => "rs",
+            cli::Mode::InkToEvm => "sol",
+        };
+
+        // Every module of the workspace is the library of its own crate
+        let workspace_pattern = format!("{{name}}/{}", scaffold::LIB);
+        if let Some(dir) = &args.workspace {
+            let inputs = batch::collect_inputs(input_dir)?;
+            let modules = inputs
+                .iter()
+                .map(|path| batch::module_name(path))
+                .collect::<Vec<_>>();
+
+            batch::ensure_unique(
+                std::iter::once((deps::TYPES.name.to_owned(), "shared types".to_owned())).chain(
+                    inputs
+                        .iter()
+                        .map(|path| (batch::module_name(path), path.display().to_string())),
+                ),
+            )?;
+
+            let generator = sol2ink::Generator::new(&args.target, &context.templates)?;
+            let types = generator.render_types(deps::TYPES.name, &context.ink)?;
+            let dependencies = deps::of(&args.mode, &args.target, args.xvm_version, &versions);
+            for path in scaffold::create_workspace(dir, &modules, &dependencies, &versions, &types)?
+            {
+                eprintln!("created {}", path.display());
+            }
+        }
+
+        let options = batch::Options {
+            input_dir,
+            output_dir,
+            extension,
+            path_pattern: match args.workspace {
+                Some(_) => Some(workspace_pattern.as_str()),
+                None => context.config.output.path.as_deref(),
+            },
+            jobs: args.jobs,
+            fingerprint: fingerprint(&args, &context),
+            use_cache: !args.no_cache,
+            mod_file: extension == "rs"
+                && context.config.output.path.is_none()
+                && args.workspace.is_none(),
+            prune: args.prune,
+        };
+
+        batch::run(
+            &options,
+            |reader| load(&args.mode, None, reader),
+            |generator, source, module_name, writer| {
+                render(
+                    &args,
+                    &context,
+                    generator,
+                    source,
+                    Some(module_name),
+                    writer,
+                )
+            },
+        )?;
+
+        return Ok(());
+    }
+
+    let mut module_name = args.module_name.clone();
+    let source = match (fetched.pop(), &args.input) {
+        (Some(item), _) => {
+            module_name = module_name.or_else(|| Some(item.module_name()));
+            item.abi
+        }
+        (None, Some(filename)) => load(
+            &args.mode,
+            args.contract.as_deref(),
+            &mut &input::open(filename)?[..],
+        )?,
+        (None, None) if !args.signatures.is_empty() => {
+            if !matches!(args.mode, cli::Mode::EvmToInk) {
+                cli::Cli::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--sig is only supported in evm-to-ink mode",
+                    )
+                    .exit();
+            }
+
+            human::parse(&args.signatures.join("\n"))?
+        }
+        (None, None) => load(
+            &args.mode,
+            args.contract.as_deref(),
+            &mut BufReader::new(io::stdin()),
+        )?,
+    };
+
+    if let Some(output) = args
+        .output
+        .as_ref()
+        .filter(|output| args.append && output.exists())
+    {
+        if !matches!(args.mode, cli::Mode::EvmToInk) {
+            cli::Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--append is only supported in evm-to-ink mode",
+                )
+                .exit();
+        }
+
+        append(&args, &context, &source, module_name.as_deref(), output)?;
+        return Ok(());
+    }
+
+    let name = module_name.clone().unwrap_or_default();
+    if let Some(dir) = &args.scaffold {
+        let name = module_name.as_deref().ok_or(Error::MissingModuleName)?;
+        let dependencies = deps::of(&args.mode, &args.target, args.xvm_version, &versions);
+        for path in scaffold::create(dir, name, &dependencies, &versions)? {
+            eprintln!("created {}", path.display());
+        }
+    }
+
+    write_single(&args, &name, |writer| {
+        render(
+            &args,
+            &context,
+            &mut None,
+            &source,
+            module_name.as_deref(),
+            writer,
+        )
+    })?;
 
     Ok(())
 }
@@ -0,0 +1,83 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Name of the manifest file that is stored next to generated outputs
+pub const MANIFEST_FILE: &str = ".sumi-manifest";
+
+/// Files Sumi generated into the output directory, so the ones
+/// left over from removed inputs could be told apart and pruned
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Output paths relative to the directory, mapped to their inputs
+    files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Loads manifest from the directory. Missing or malformed manifest is treated as empty.
+    pub fn load(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), Error> {
+        let path = dir.join(MANIFEST_FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| Error::WriteOutput { path, inner: e })
+    }
+}
+
+fn entry_name(dir: &Path, output: &Path) -> String {
+    output
+        .strip_prefix(dir)
+        .unwrap_or(output)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Records outputs of the run, given as `(output, input)` pairs, in the manifest of the
+/// directory. Files generated by earlier runs from inputs that are gone are deleted
+/// with `prune`, otherwise they are kept in the manifest and reported.
+pub fn update<I>(dir: &Path, outputs: I, prune: bool) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (PathBuf, String)>,
+{
+    let previous = Manifest::load(dir);
+    let mut manifest = Manifest {
+        files: outputs
+            .into_iter()
+            .map(|(output, input)| (entry_name(dir, &output), input))
+            .collect(),
+    };
+
+    let stale: Vec<_> = previous
+        .files
+        .into_iter()
+        .filter(|(name, _)| !manifest.files.contains_key(name))
+        .collect();
+
+    if prune {
+        for (name, input) in &stale {
+            let path = dir.join(name);
+            match fs::remove_file(&path) {
+                Ok(()) => eprintln!("removed {} (generated from {input})", path.display()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(Error::WriteOutput { path, inner: e }),
+            }
+        }
+    } else if !stale.is_empty() {
+        eprintln!(
+            "warning: {} generated files have no input anymore, remove them with --prune",
+            stale.len()
+        );
+        manifest.files.extend(stale);
+    }
+
+    manifest.save(dir)
+}
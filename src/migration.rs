@@ -0,0 +1,202 @@
+use crate::error::Error;
+use std::{fmt::Write, fs, path::Path};
+
+/// File the notes are written to, next to the generated modules
+pub const MIGRATION_FILE: &str = "MIGRATION.md";
+
+/// Message of a generated module
+#[derive(Debug, PartialEq, Eq)]
+struct Message {
+    name: String,
+    params: String,
+}
+
+impl Message {
+    fn signature(&self) -> String {
+        format!("{}({})", self.name, self.params)
+    }
+}
+
+/// Extracts messages from `#[ink(message)]` functions of the module
+fn messages(module: &str) -> Vec<Message> {
+    let mut lines = module.lines().map(str::trim);
+    let mut messages = Vec::new();
+
+    while let Some(line) = lines.next() {
+        if !line.starts_with("#[ink(message") {
+            continue;
+        }
+
+        let declaration = match lines.find(|line| line.starts_with("pub fn ")) {
+            Some(declaration) => declaration,
+            None => break,
+        };
+
+        let declaration = &declaration["pub fn ".len()..];
+        let (name, rest) = declaration.split_once('(').unwrap_or((declaration, ""));
+        let params = rest
+            .rsplit_once(')')
+            .map_or(rest, |(params, _)| params)
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty() && !param.ends_with("self"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        messages.push(Message {
+            name: name.trim().to_owned(),
+            params,
+        });
+    }
+
+    messages
+}
+
+/// Describes changes of the module messages in Markdown, `None` if there are none
+pub fn notes(module_name: &str, previous: &str, current: &str) -> Option<String> {
+    let before = messages(previous);
+    let after = messages(current);
+
+    let mut removed: Vec<_> = before
+        .iter()
+        .filter(|old| after.iter().all(|new| new.name != old.name))
+        .collect();
+
+    let mut added: Vec<_> = after
+        .iter()
+        .filter(|new| before.iter().all(|old| old.name != new.name))
+        .collect();
+
+    let changed: Vec<_> = before
+        .iter()
+        .filter_map(|old| {
+            let new = after.iter().find(|new| new.name == old.name)?;
+            (new.params != old.params).then_some((old, new))
+        })
+        .collect();
+
+    // Removed message with the same parameters as an added one was most likely renamed
+    let mut renamed = Vec::new();
+    removed.retain(
+        |old| match added.iter().position(|new| new.params == old.params) {
+            Some(index) => {
+                renamed.push((*old, added.remove(index)));
+                false
+            }
+            None => true,
+        },
+    );
+
+    if removed.is_empty() && added.is_empty() && changed.is_empty() && renamed.is_empty() {
+        return None;
+    }
+
+    let mut notes = format!("## `{module_name}`\n");
+    if !removed.is_empty() {
+        notes.push_str("\nRemoved messages, calls to them have to be dropped:\n\n");
+        for message in &removed {
+            let _ = writeln!(notes, "- `{}`", message.signature());
+        }
+    }
+
+    if !renamed.is_empty() {
+        notes.push_str("\nRenamed messages:\n\n");
+        for (old, new) in &renamed {
+            let _ = writeln!(notes, "- `{}` -> `{}`", old.signature(), new.signature());
+        }
+    }
+
+    if !changed.is_empty() {
+        notes.push_str("\nMessages with changed parameters:\n\n");
+        for (old, new) in &changed {
+            let _ = writeln!(notes, "- `{}` -> `{}`", old.signature(), new.signature());
+        }
+    }
+
+    if !added.is_empty() {
+        notes.push_str("\nNew messages:\n\n");
+        for message in &added {
+            let _ = writeln!(notes, "- `{}`", message.signature());
+        }
+    }
+
+    Some(notes)
+}
+
+/// Output file contents before regeneration
+pub struct Snapshot {
+    module_name: String,
+    previous: Option<String>,
+}
+
+impl Snapshot {
+    /// Has to be taken before the output is truncated
+    pub fn take(module_name: &str, output: &Path) -> Self {
+        Snapshot {
+            module_name: module_name.to_owned(),
+            previous: fs::read_to_string(output).ok(),
+        }
+    }
+
+    /// Notes describing how the regenerated output differs from the snapshot
+    pub fn notes(&self, output: &Path) -> Option<String> {
+        let previous = self.previous.as_ref()?;
+        let current = fs::read_to_string(output).ok()?;
+        notes(&self.module_name, previous, &current)
+    }
+}
+
+/// Writes notes of all changed modules to the migration file of the directory
+pub fn write(dir: &Path, notes: &[String]) -> Result<(), Error> {
+    let path = dir.join(MIGRATION_FILE);
+    let contents = format!(
+        "# Migration notes\n\nAPI of the generated wrappers has changed since the previous generation.\n\n{}",
+        notes.join("\n")
+    );
+
+    fs::write(&path, contents).map_err(|e| Error::WriteOutput { path, inner: e })?;
+    eprintln!(
+        "wrapper API has changed, see {}",
+        dir.join(MIGRATION_FILE).display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(messages: &[&str]) -> String {
+        messages
+            .iter()
+            .map(|message| {
+                format!("        #[ink(message)]\n        pub fn {message} -> bool {{\n")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn describes_changes() {
+        let previous = module(&[
+            "transfer(&mut self, to: H160, value: U256)",
+            "burn(&mut self, value: U256)",
+            "mint(&mut self, to: H160)",
+        ]);
+
+        let current = module(&[
+            "transfer(&mut self, to: H160, value: U256, data: Vec<u8>)",
+            "destroy(&mut self, value: U256)",
+            "mint(&mut self, to: H160)",
+        ]);
+
+        let notes = notes("erc20", &previous, &current).unwrap();
+        assert!(notes.contains("- `burn(value: U256)` -> `destroy(value: U256)`"));
+        assert!(notes.contains(
+            "- `transfer(to: H160, value: U256)` -> `transfer(to: H160, value: U256, data: Vec<u8>)`"
+        ));
+        assert!(!notes.contains("mint"));
+
+        assert_eq!(super::notes("erc20", &previous, &previous), None);
+    }
+}
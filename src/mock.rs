@@ -0,0 +1,116 @@
+use crate::{error::Error, sol2ink};
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use serde::Serialize;
+use tinytemplate::TinyTemplate;
+
+static MOCK_TEMPLATE: &str = include_str!("../templates/solidity-mock.txt");
+
+#[derive(Serialize)]
+struct Function {
+    name: String,
+    signature: String,
+    params: String,
+    returns: String,
+    mutability: String,
+    payable: bool,
+
+    /// View functions cannot modify state, so they are not recorded
+    records: bool,
+}
+
+#[derive(Serialize)]
+struct Mock {
+    module_name: String,
+    contract_name: String,
+    functions: Vec<Function>,
+    skipped: Vec<String>,
+}
+
+/// Solidity declaration of the parameter type, `None` if mock cannot express it
+fn declaration(param: &json::JsonValue, location: &str) -> Option<String> {
+    let ty = param["type"].as_str()?;
+
+    // Tuples would require struct definitions
+    if ty.starts_with("tuple") {
+        return None;
+    }
+
+    Some(if ty == "string" || ty == "bytes" || ty.ends_with(']') {
+        format!("{ty} {location}")
+    } else {
+        ty.to_owned()
+    })
+}
+
+fn declarations(params: &json::JsonValue, location: &str) -> Option<String> {
+    params
+        .members()
+        .map(|param| declaration(param, location))
+        .collect::<Option<Vec<_>>>()
+        .map(|declarations| declarations.join(", "))
+}
+
+/// Name of the mock contract of the module
+pub fn contract_name(module_name: &str) -> String {
+    format!("{}Mock", module_name.to_case(Case::UpperCamel))
+}
+
+/// Renders Solidity contract implementing the ABI with settable return data
+pub fn render(json: &json::JsonValue, module_name: &str) -> Result<String, Error> {
+    let mut functions = Vec::new();
+    let mut skipped = Vec::new();
+
+    for function in json.members().filter(|item| item["type"] == "function") {
+        let name = function["name"].as_str().unwrap_or_default();
+        let signature = format!(
+            "{name}({})",
+            function["inputs"]
+                .members()
+                .filter_map(|input| input["type"].as_str())
+                .join(",")
+        );
+
+        let (params, returns) = match (
+            declarations(&function["inputs"], "calldata"),
+            declarations(&function["outputs"], "memory"),
+        ) {
+            (Some(params), Some(returns)) => (params, returns),
+            _ => {
+                skipped.push(signature);
+                continue;
+            }
+        };
+
+        let state_mutability = sol2ink::state_mutability(function).unwrap_or("nonpayable");
+        let records = !matches!(state_mutability, "view" | "pure");
+
+        functions.push(Function {
+            name: name.to_owned(),
+            signature,
+            params,
+            returns,
+            mutability: match state_mutability {
+                "payable" => "payable",
+                "view" | "pure" => "view",
+                _ => "",
+            }
+            .to_owned(),
+            payable: state_mutability == "payable",
+            records,
+        });
+    }
+
+    let mock = Mock {
+        module_name: module_name.to_owned(),
+        contract_name: contract_name(module_name),
+        functions,
+        skipped,
+    };
+
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&tinytemplate::format_unescaped);
+    template.add_template("mock", MOCK_TEMPLATE)?;
+
+    Ok(template.render("mock", &mock)?)
+}
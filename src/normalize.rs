@@ -0,0 +1,68 @@
+use crate::error::Error;
+use serde_json::Value;
+
+/// Brings ABI to the canonical form, so equivalent ABIs are equal byte by byte:
+/// object keys are sorted, `null` fields are dropped and entries are sorted by
+/// their type, name and inputs. Order of parameters is meaningful and is kept.
+pub fn normalize(abi: Value) -> Result<Value, Error> {
+    let mut entries = match strip_nulls(abi) {
+        Value::Array(entries) => entries,
+        _ => return Err(Error::Metadata("ABI should be an array".to_owned())),
+    };
+
+    entries.sort_by_cached_key(|entry| {
+        (
+            entry["type"].as_str().unwrap_or_default().to_owned(),
+            entry["name"].as_str().unwrap_or_default().to_owned(),
+            entry["inputs"].to_string(),
+        )
+    });
+
+    Ok(Value::Array(entries))
+}
+
+/// Drops `null` fields, inserting the rest in the sorted order
+fn strip_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut fields = object
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, strip_nulls(value)))
+                .collect::<Vec<_>>();
+
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(fields.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(strip_nulls).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_entries_and_keys() {
+        let abi = serde_json::json!([
+            {"type": "function", "name": "transfer", "outputs": null, "inputs": [
+                {"type": "address", "name": "to"},
+                {"type": "uint256", "name": "amount"}
+            ]},
+            {"type": "event", "name": "Transfer", "anonymous": false, "inputs": []},
+            {"type": "function", "name": "approve", "inputs": []}
+        ]);
+
+        let normalized = normalize(abi).unwrap();
+        assert_eq!(
+            normalized.to_string(),
+            concat!(
+                r#"[{"anonymous":false,"inputs":[],"name":"Transfer","type":"event"},"#,
+                r#"{"inputs":[],"name":"approve","type":"function"},"#,
+                r#"{"inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"#,
+                r#""name":"transfer","type":"function"}]"#
+            )
+        );
+    }
+}
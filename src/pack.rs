@@ -0,0 +1,183 @@
+use crate::error::Error;
+use hex::ToHex;
+use sha3::{Digest, Keccak256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Directory git template packs are cloned into, relative to the working directory
+pub const PACK_CACHE: &str = ".sumi-templates";
+
+/// Prefix of the pack integrity, naming the hash function
+const INTEGRITY_PREFIX: &str = "keccak256:";
+
+/// Where a template pack comes from
+#[derive(Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Repository cloned at the branch or tag, default branch if `None`
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+
+    /// Directory on the disk
+    Local(PathBuf),
+}
+
+impl Source {
+    /// Parses `github:<org>/<repo>#<ref>`, `git+<url>#<ref>`,
+    /// a URL ending with `.git` or a local directory
+    pub fn parse(source: &str) -> Self {
+        let (location, reference) = match source.rsplit_once('#') {
+            Some((location, reference)) => (location, Some(reference.to_owned())),
+            None => (source, None),
+        };
+
+        if let Some(repository) = location.strip_prefix("github:") {
+            Source::Git {
+                url: format!(
+                    "https://github.com/{}.git",
+                    repository.trim_end_matches(".git")
+                ),
+                reference,
+            }
+        } else if let Some(url) = location.strip_prefix("git+") {
+            Source::Git {
+                url: url.to_owned(),
+                reference,
+            }
+        } else if location.contains("://") && location.ends_with(".git") {
+            Source::Git {
+                url: location.to_owned(),
+                reference,
+            }
+        } else {
+            Source::Local(PathBuf::from(source))
+        }
+    }
+}
+
+/// Hash of the `.txt` templates of the pack directory, their names and contents
+pub fn integrity(dir: &Path) -> Result<String, Error> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::ReadInput {
+        path: dir.to_owned(),
+        inner: e,
+    })?;
+
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "txt")
+        {
+            templates.push(path);
+        }
+    }
+
+    // Directory order differs between systems
+    templates.sort();
+
+    let mut hasher = Keccak256::new();
+    for path in templates {
+        let contents = fs::read(&path).map_err(|e| Error::ReadInput {
+            path: path.clone(),
+            inner: e,
+        })?;
+
+        hasher.update(
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .as_bytes(),
+        );
+        hasher.update([0u8]);
+        hasher.update(contents);
+        hasher.update([0u8]);
+    }
+
+    Ok(format!(
+        "{INTEGRITY_PREFIX}{}",
+        hasher.finalize().encode_hex::<String>()
+    ))
+}
+
+/// Clones the repository into the pack cache, unless it was cloned before
+fn clone(url: &str, reference: Option<&str>) -> Result<PathBuf, Error> {
+    let mut hasher = Keccak256::new();
+    hasher.update(url.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(reference.unwrap_or_default().as_bytes());
+    let dir = Path::new(PACK_CACHE).join(&hasher.finalize().encode_hex::<String>()[..16]);
+
+    if dir.is_dir() {
+        return Ok(dir);
+    }
+
+    let mut command = Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(reference) = reference {
+        command.args(["--branch", reference]);
+    }
+
+    let output = command.arg(url).arg(&dir).output()?;
+    if !output.status.success() {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(Error::TemplatePack {
+            pack: url.to_owned(),
+            reason: format!(
+                "git clone failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    Ok(dir)
+}
+
+/// Resolves the pack to a local directory of templates, checking it against
+/// the integrity if one is pinned. Returns the directory and its integrity.
+pub fn resolve(source: &str, pinned: Option<&str>) -> Result<(PathBuf, String), Error> {
+    let dir = match Source::parse(source) {
+        Source::Git { url, reference } => clone(&url, reference.as_deref())?,
+        Source::Local(dir) => dir,
+    };
+
+    let found = integrity(&dir)?;
+    match pinned {
+        Some(pinned) if pinned != found => Err(Error::TemplatePack {
+            pack: source.to_owned(),
+            reason: format!("integrity {pinned} is pinned, but the templates have {found}"),
+        }),
+        _ => Ok((dir, found)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sources() {
+        assert_eq!(
+            Source::parse("github:org/sumi-templates#v1"),
+            Source::Git {
+                url: "https://github.com/org/sumi-templates.git".to_owned(),
+                reference: Some("v1".to_owned()),
+            }
+        );
+        assert_eq!(
+            Source::parse("git+ssh://git@example.com/templates"),
+            Source::Git {
+                url: "ssh://git@example.com/templates".to_owned(),
+                reference: None,
+            }
+        );
+        assert_eq!(
+            Source::parse("../templates"),
+            Source::Local(PathBuf::from("../templates"))
+        );
+    }
+}
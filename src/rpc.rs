@@ -0,0 +1,392 @@
+use crate::{artifact, error::Error, fetch::Fetched, warning::Warning};
+use hex::ToHex;
+use serde_json::{json, Value};
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Default IPFS gateway to download contract metadata from
+pub const IPFS_GATEWAY: &str = "https://ipfs.io";
+
+/// Hex placeholder of an unlinked library address, i.e. `__$<34 hex digits>$__`
+const PLACEHOLDER_LEN: usize = 40;
+
+/// Bytes of the address deployed libraries check they are not called directly at
+const LIBRARY_ADDRESS: Range<usize> = 1..21;
+
+pub struct Options {
+    /// Ethereum JSON-RPC endpoint of the node, i.e. of a Frontier based chain
+    pub url: String,
+    pub address: String,
+
+    /// Directory of build artifacts of known contracts
+    pub registry: Option<PathBuf>,
+    pub ipfs_gateway: String,
+}
+
+/// Hash of the contract metadata solc appends to the runtime bytecode
+#[derive(Debug, PartialEq, Eq)]
+pub enum MetadataHash {
+    /// IPFS multihash, i.e. `0x1220...`
+    Ipfs(Vec<u8>),
+    Swarm(Vec<u8>),
+}
+
+impl MetadataHash {
+    /// IPFS content identifier of the metadata file, if it was published there
+    pub fn cid(&self) -> Option<String> {
+        match self {
+            MetadataHash::Ipfs(multihash) => Some(bs58::encode(multihash).into_string()),
+            MetadataHash::Swarm(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataHash::Ipfs(_) => write!(f, "ipfs {}", self.cid().unwrap_or_default()),
+            MetadataHash::Swarm(hash) => write!(f, "swarm {}", hash.encode_hex::<String>()),
+        }
+    }
+}
+
+/// Minimal reader of the CBOR map solc encodes metadata hash into
+struct Cbor<'a>(&'a [u8]);
+
+impl<'a> Cbor<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.0.len() < length {
+            return None;
+        }
+
+        let (head, tail) = self.0.split_at(length);
+        self.0 = tail;
+        Some(head)
+    }
+
+    /// Major type and argument of the next item
+    fn header(&mut self) -> Option<(u8, usize)> {
+        let initial = *self.take(1)?.first()?;
+        let argument = match initial & 0x1f {
+            value @ 0..=23 => value as usize,
+            24 => self.take(1)?[0] as usize,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().ok()?) as usize,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().ok()?) as usize,
+            _ => return None,
+        };
+
+        Some((initial >> 5, argument))
+    }
+
+    /// Content of a byte or text string, `None` for other values
+    fn value(&mut self) -> Option<Option<&'a [u8]>> {
+        match self.header()? {
+            (2 | 3, length) => Some(Some(self.take(length)?)),
+            (0 | 1 | 7, _) => Some(None),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts metadata hash from the CBOR encoded tail of the runtime bytecode
+pub fn metadata_hash(code: &[u8]) -> Option<MetadataHash> {
+    let (code, length) = code.split_at(code.len().checked_sub(2)?);
+    let length = u16::from_be_bytes(length.try_into().ok()?) as usize;
+    let mut cbor = Cbor(&code[code.len().checked_sub(length)?..]);
+
+    let (major, entries) = cbor.header()?;
+    if major != 5 {
+        return None;
+    }
+
+    for _ in 0..entries {
+        let key = cbor.value()??;
+        let value = cbor.value()?;
+
+        match (key, value) {
+            (b"ipfs", Some(hash)) => return Some(MetadataHash::Ipfs(hash.to_vec())),
+            (b"bzzr0" | b"bzzr1", Some(hash)) => return Some(MetadataHash::Swarm(hash.to_vec())),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn decode_code(code: &str) -> Option<Vec<u8>> {
+    hex::decode(code.trim().trim_start_matches("0x")).ok()
+}
+
+/// Runtime bytecode of a library starts with `PUSH20 <address> ADDRESS EQ`, which
+/// makes state modifying functions revert unless called with `DELEGATECALL`
+fn is_library_code(code: &[u8]) -> bool {
+    code.first() == Some(&0x73)
+        && code.get(LIBRARY_ADDRESS.end..LIBRARY_ADDRESS.end + 2) == Some(&[0x30, 0x14][..])
+}
+
+/// Runtime bytecode of a build artifact with its unlinked library placeholders zeroed.
+/// Ranges of the addresses only known once the contract is deployed are returned too.
+fn decode_artifact_code(code: &str) -> Option<(Vec<u8>, Vec<Range<usize>>)> {
+    let mut rest = code.trim().trim_start_matches("0x");
+    let mut code = Vec::new();
+    let mut links = Vec::new();
+
+    while !rest.is_empty() {
+        let end = rest.find("__").unwrap_or(rest.len());
+        code.extend(hex::decode(&rest[..end]).ok()?);
+        rest = &rest[end..];
+
+        if !rest.is_empty() {
+            rest = rest.get(PLACEHOLDER_LEN..)?;
+            links.push(code.len()..code.len() + PLACEHOLDER_LEN / 2);
+            code.resize(code.len() + PLACEHOLDER_LEN / 2, 0);
+        }
+    }
+
+    if is_library_code(&code) {
+        links.push(LIBRARY_ADDRESS);
+    }
+
+    Some((code, links))
+}
+
+/// Whether the deployed code is the artifact code with the addresses filled in
+fn is_deployed_from(code: &[u8], known: &[u8], links: &[Range<usize>]) -> bool {
+    code.len() == known.len()
+        && code
+            .iter()
+            .zip(known)
+            .enumerate()
+            .all(|(i, (a, b))| a == b || links.iter().any(|link| link.contains(&i)))
+}
+
+/// Runtime bytecode of the build artifact, Hardhat and Foundry layouts are supported
+fn artifact_code(path: &Path) -> Option<(Option<String>, String, Vec<u8>, Vec<Range<usize>>)> {
+    let contents = fs::read_to_string(path).ok()?;
+    let artifact: Value = serde_json::from_str(&contents).ok()?;
+
+    let code = match &artifact["deployedBytecode"] {
+        Value::String(code) => code.as_str(),
+        code => code["object"]
+            .as_str()
+            .or_else(|| artifact["evm"]["deployedBytecode"]["object"].as_str())?,
+    };
+
+    let name = artifact["contractName"]
+        .as_str()
+        .map(str::to_owned)
+        .or_else(|| path.file_stem()?.to_str().map(str::to_owned));
+    let abi = artifact::read_abi(&mut contents.as_bytes()).ok()?;
+
+    let (code, links) = decode_artifact_code(code)?;
+    Some((name, abi, code, links))
+}
+
+/// Looks for the artifact with the same metadata hash, or the same bytecode
+/// when the contract was compiled without metadata
+fn find_in_registry(
+    registry: &Path,
+    code: &[u8],
+) -> Result<Option<(Option<String>, String)>, Error> {
+    let hash = metadata_hash(code);
+    let mut pending = vec![registry.to_owned()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| Error::ReadInput {
+            path: dir.clone(),
+            inner: e,
+        })?;
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "json")
+            {
+                continue;
+            }
+
+            if let Some((name, abi, known, links)) = artifact_code(&path) {
+                let matches = match &hash {
+                    Some(hash) => metadata_hash(&known).as_ref() == Some(hash),
+                    None => is_deployed_from(code, &known, &links),
+                };
+
+                if matches {
+                    return Ok(Some((name, abi)));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Downloads ABI of the metadata file published to IPFS
+fn fetch_metadata(
+    agent: &ureq::Agent,
+    gateway: &str,
+    cid: &str,
+) -> Result<(Option<String>, String), String> {
+    let body = agent
+        .get(&format!("{}/ipfs/{cid}", gateway.trim_end_matches('/')))
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+
+    let metadata: Value =
+        serde_json::from_str(&body).map_err(|e| format!("malformed metadata: {e}"))?;
+
+    let abi = &metadata["output"]["abi"];
+    if !abi.is_array() {
+        return Err("metadata does not contain ABI".to_owned());
+    }
+
+    let name = metadata["settings"]["compilationTarget"]
+        .as_object()
+        .and_then(|target| target.values().next())
+        .and_then(Value::as_str)
+        .map(str::to_owned);
+
+    Ok((name, abi.to_string()))
+}
+
+/// Reads deployed bytecode of the contract from the node
+fn get_code(agent: &ureq::Agent, url: &str, address: &str) -> Result<Vec<u8>, String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_getCode",
+        "params": [address, "latest"],
+    });
+
+    let body = agent
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(&request.to_string())
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+
+    let response: Value =
+        serde_json::from_str(&body).map_err(|e| format!("malformed response: {e}"))?;
+
+    if let Some(message) = response["error"]["message"].as_str() {
+        return Err(message.to_owned());
+    }
+
+    let code = response["result"]
+        .as_str()
+        .and_then(decode_code)
+        .ok_or_else(|| "malformed eth_getCode result".to_owned())?;
+
+    if code.is_empty() {
+        return Err("no contract is deployed at the address".to_owned());
+    }
+
+    Ok(code)
+}
+
+/// Identifies the contract deployed at the address by its bytecode, taking
+/// the ABI from the local registry first and from IPFS otherwise
+pub fn resolve(options: &Options) -> Result<Fetched, Error> {
+    let error = |reason| Error::Fetch {
+        address: options.address.clone(),
+        reason,
+    };
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+    let code = get_code(&agent, &options.url, &options.address).map_err(error)?;
+    if is_library_code(&code) {
+        let warning = Warning::Library {
+            module: options.address.clone(),
+        };
+        eprintln!("warning: {warning}");
+    }
+
+    let found = match &options.registry {
+        Some(registry) => find_in_registry(registry, &code)?,
+        None => None,
+    };
+
+    let (name, abi) = match (found, metadata_hash(&code)) {
+        (Some(found), _) => found,
+        (None, Some(hash)) => match hash.cid() {
+            Some(cid) => fetch_metadata(&agent, &options.ipfs_gateway, &cid)
+                .map_err(|reason| error(format!("unable to get metadata {hash}: {reason}")))?,
+            None => {
+                return Err(error(format!(
+                    "metadata {hash} is not in the registry and cannot be downloaded"
+                )))
+            }
+        },
+        (None, None) => {
+            return Err(error(
+                "bytecode carries no metadata hash and is not in the registry".to_owned(),
+            ))
+        }
+    };
+
+    Ok(Fetched {
+        address: options.address.clone(),
+        name,
+        abi,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_metadata_hash() {
+        let mut code = hex::decode("6080604052600080fd").unwrap();
+        let tail = [
+            "a2",
+            "64697066735822",
+            "1220",
+            &"00".repeat(32),
+            "64736f6c6343",
+            "000811",
+            "0033",
+        ];
+        code.extend(hex::decode(tail.concat()).unwrap());
+
+        let hash = metadata_hash(&code).unwrap();
+        assert_eq!(
+            hash.cid().unwrap(),
+            "QmNLei78zWmzUdbeRB3CiUfAizWUrbeeZh5K1rhAQKCh51"
+        );
+
+        assert_eq!(metadata_hash(&code[..code.len() - 1]), None);
+    }
+
+    #[test]
+    fn matches_linked_code() {
+        let placeholder = format!("__${}$__", "ab".repeat(17));
+        let artifact = format!("0x73{}30146080{placeholder}00", "00".repeat(20));
+        let (known, links) = decode_artifact_code(&artifact).unwrap();
+        assert_eq!(links, [25..45, LIBRARY_ADDRESS]);
+
+        let library = "11".repeat(20);
+        let address = "22".repeat(20);
+        let deployed = hex::decode(format!("73{library}30146080{address}00")).unwrap();
+        assert!(is_library_code(&deployed));
+        assert!(is_deployed_from(&deployed, &known, &links));
+
+        let changed = hex::decode(format!("73{library}30146080{address}01")).unwrap();
+        assert!(!is_deployed_from(&changed, &known, &links));
+    }
+}
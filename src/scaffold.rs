@@ -0,0 +1,186 @@
+use crate::{
+    deps::{self, Dependency, Versions},
+    error::Error,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{Array, Document, Item, Value};
+
+/// Generated module of the crate, the manifest points `[lib]` to it
+pub const LIB: &str = "lib.rs";
+
+static GITIGNORE: &str = "/target\n";
+
+/// Manifest of the workspace, members are added by [`add_members`]
+static WORKSPACE: &str = "[workspace]\nresolver = \"2\"\n";
+
+/// Manifest of the `cargo contract` crate, dependencies are added by [`deps::write`]
+fn manifest(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+name = "{name}"
+path = "{LIB}"
+crate-type = ["cdylib", "rlib"]
+
+[features]
+default = ["std"]
+std = []
+ink-as-dependency = []
+"#
+    )
+}
+
+/// Manifest of the library crate defining the EVM types, see [`deps::TYPES`]
+fn types_manifest() -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+path = "{LIB}"
+
+[features]
+default = ["std"]
+std = []
+"#,
+        deps::TYPES.name
+    )
+}
+
+fn create_dir(dir: &Path) -> Result<(), Error> {
+    fs::create_dir_all(dir).map_err(|e| Error::WriteOutput {
+        path: dir.to_path_buf(),
+        inner: e,
+    })
+}
+
+/// Creates `cargo contract` crate around the module in the directory: the manifest
+/// with dependencies of the generated code and `.gitignore`. Existing files are kept,
+/// only the missing dependencies are added to the manifest. Returns the created files.
+pub fn create(
+    dir: &Path,
+    name: &str,
+    dependencies: &[&Dependency],
+    versions: &Versions,
+) -> Result<Vec<PathBuf>, Error> {
+    create_dir(dir)?;
+
+    let manifest_path = dir.join("Cargo.toml");
+    let created = write_missing([
+        (manifest_path.clone(), manifest(name)),
+        (dir.join(".gitignore"), GITIGNORE.to_owned()),
+    ])?;
+
+    deps::write(&manifest_path, dependencies, versions)?;
+    Ok(created)
+}
+
+/// Creates Cargo workspace in the directory with a `cargo contract` crate per module,
+/// see [`create`], and the crate defining the EVM types the modules share, with
+/// `types` as its library. Members missing from an existing workspace are added.
+pub fn create_workspace(
+    dir: &Path,
+    modules: &[String],
+    dependencies: &[&Dependency],
+    versions: &Versions,
+    types: &str,
+) -> Result<Vec<PathBuf>, Error> {
+    create_dir(dir)?;
+
+    let members = std::iter::once(deps::TYPES.name)
+        .chain(modules.iter().map(String::as_str))
+        .collect::<Vec<_>>();
+
+    let manifest_path = dir.join("Cargo.toml");
+    let mut created = write_missing([
+        (manifest_path.clone(), WORKSPACE.to_owned()),
+        (dir.join(".gitignore"), GITIGNORE.to_owned()),
+    ])?;
+
+    add_members(&manifest_path, &members)?;
+
+    let types_dir = dir.join(deps::TYPES.name);
+    create_dir(&types_dir)?;
+
+    let types_manifest_path = types_dir.join("Cargo.toml");
+    created.extend(write_missing([(
+        types_manifest_path.clone(),
+        types_manifest(),
+    )])?);
+
+    deps::write(&types_manifest_path, &deps::of_types(versions), versions)?;
+
+    // The library is generated, so it is always replaced
+    let lib = types_dir.join(LIB);
+    fs::write(&lib, types).map_err(|e| Error::WriteOutput {
+        path: lib.clone(),
+        inner: e,
+    })?;
+
+    let dependencies = dependencies
+        .iter()
+        .copied()
+        .chain(std::iter::once(&deps::TYPES))
+        .collect::<Vec<_>>();
+
+    for module in modules {
+        created.extend(create(&dir.join(module), module, &dependencies, versions)?);
+    }
+
+    Ok(created)
+}
+
+/// Adds the crates missing from `workspace.members` of the manifest
+fn add_members(path: &Path, members: &[&str]) -> Result<(), Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+        path: path.to_owned(),
+        inner: e,
+    })?;
+
+    let invalid = |e: &dyn std::fmt::Display| Error::Manifest(format!("{}: {e}", path.display()));
+    let mut manifest = text.parse::<Document>().map_err(|e| invalid(&e))?;
+
+    let existing = manifest["workspace"]["members"]
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or_else(|| invalid(&"`workspace.members` is not an array"))?;
+
+    for member in members {
+        if !existing.iter().any(|item| item.as_str() == Some(member)) {
+            existing.push(*member);
+        }
+    }
+
+    fs::write(path, manifest.to_string()).map_err(|e| Error::WriteOutput {
+        path: path.to_owned(),
+        inner: e,
+    })
+}
+
+/// Writes the files that do not exist yet, returns their paths
+fn write_missing<const N: usize>(files: [(PathBuf, String); N]) -> Result<Vec<PathBuf>, Error> {
+    let mut created = Vec::new();
+    for (path, contents) in files {
+        if path.exists() {
+            continue;
+        }
+
+        fs::write(&path, contents).map_err(|e| Error::WriteOutput {
+            path: path.clone(),
+            inner: e,
+        })?;
+
+        created.push(path);
+    }
+
+    Ok(created)
+}
@@ -0,0 +1,32 @@
+use crate::{error::Error, sol2ink::Model};
+use convert_case::{Case, Casing};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tinytemplate::{format_unescaped, TinyTemplate};
+
+static SCRIPTS_TEMPLATE: &str = include_str!("../templates/call-scripts.txt");
+
+#[derive(Serialize)]
+struct Context<'a> {
+    script_path: String,
+    model: &'a Model,
+}
+
+/// Path of the script relative to the artifacts directory
+pub fn path(module_name: &str) -> PathBuf {
+    Path::new("scripts").join(format!("{}.sh", module_name.to_case(Case::Snake)))
+}
+
+/// Renders shell script with a `cargo contract call` invocation of every message
+pub fn render(model: &Model, module_name: &str) -> Result<String, Error> {
+    let mut template = TinyTemplate::new();
+    template.set_default_formatter(&format_unescaped);
+    template.add_template("scripts", SCRIPTS_TEMPLATE)?;
+
+    let context = Context {
+        script_path: path(module_name).display().to_string(),
+        model,
+    };
+
+    Ok(template.render("scripts", &context)?)
+}
@@ -0,0 +1,130 @@
+use crate::{
+    error::Error,
+    filter::Filter,
+    sol2ink::{self, signature},
+};
+use convert_case::{Case, Casing};
+use hex::ToHex;
+use itertools::Itertools;
+use json::JsonValue;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Function of a contract and the message wrapping it
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub contract: String,
+    pub selector: String,
+    pub signature: String,
+
+    /// Generated message, `None` if the function is not wrapped
+    pub message: Option<String>,
+
+    /// Selector of the message, which overloaded functions share
+    pub message_selector: Option<String>,
+}
+
+/// Maps every function of the ABI to the message generated for it
+pub fn entries(
+    contract: &str,
+    abi: &JsonValue,
+    filter: &Filter,
+    include_views: bool,
+) -> Result<Vec<Entry>, Error> {
+    if !abi.is_array() {
+        return Err(Error::Metadata("ABI should be an array".to_owned()));
+    }
+
+    let wrapped: HashMap<usize, &str> =
+        sol2ink::functions(abi, filter, Default::default(), include_views)
+            .map(|(index, item)| (index, item["name"].as_str().unwrap_or_default()))
+            .collect();
+
+    let overloads = wrapped.values().counts();
+
+    Ok(abi
+        .members()
+        .enumerate()
+        .filter(|(_, item)| item["type"] == "function")
+        .map(|(index, item)| {
+            let signature = signature(item);
+            let selector = sol2ink::selector(&signature);
+
+            // Overloaded functions share the message ink! selects by its name
+            let message_selector = wrapped.get(&index).map(|name| match overloads[name] {
+                1 => selector,
+                _ => sol2ink::message_selector(&name.to_case(Case::Snake)),
+            });
+
+            Entry {
+                contract: contract.to_owned(),
+                selector: format!("0x{}", selector.encode_hex::<String>()),
+                message: wrapped.get(&index).map(|name| name.to_case(Case::Snake)),
+                message_selector: message_selector
+                    .map(|selector| format!("0x{}", selector.encode_hex::<String>())),
+                signature,
+            }
+        })
+        .collect())
+}
+
+/// Quotes the CSV field if needed, signatures contain commas
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn to_csv(entries: &[Entry]) -> String {
+    let rows = entries.iter().map(|entry| {
+        [
+            entry.contract.as_str(),
+            &entry.selector,
+            &entry.signature,
+            entry.message.as_deref().unwrap_or_default(),
+            entry.message_selector.as_deref().unwrap_or_default(),
+        ]
+        .map(csv_field)
+        .join(",")
+    });
+
+    std::iter::once("contract,selector,signature,message,message_selector".to_owned())
+        .chain(rows)
+        .map(|row| row + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_overloads_to_shared_message() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+                 "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                            {"name": "id", "type": "uint256"}]},
+                {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+                 "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                            {"name": "id", "type": "uint256"}, {"name": "data", "type": "bytes"}]},
+                {"type": "function", "name": "ownerOf", "stateMutability": "view",
+                 "inputs": [{"name": "id", "type": "uint256"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let entries = entries("nft", &abi, &Filter::default(), false).unwrap();
+        assert_eq!(
+            to_csv(&entries),
+            concat!(
+                "contract,selector,signature,message,message_selector\n",
+                "nft,0x42842e0e,\"safeTransferFrom(address,address,uint256)\",safe_transfer_from,0x8c474972\n",
+                "nft,0xb88d4fde,\"safeTransferFrom(address,address,uint256,bytes)\",safe_transfer_from,0x8c474972\n",
+                "nft,0x6352211e,ownerOf(uint256),,\n",
+            )
+        );
+    }
+}
@@ -1,16 +1,222 @@
-use crate::error::Error;
+use crate::{
+    append,
+    cli::Target,
+    docs,
+    error::{Error, ParamError},
+    filter::Filter,
+    gas,
+    hash::{self, Keccak},
+    ident, stamp,
+    warning::{SkipReason, Warning},
+};
+use blake2::{digest::consts::U32, Blake2b, Digest};
 use convert_case::{Case, Casing};
-use ethabi::ParamType;
+use ethabi::{ParamType, Token};
 use hex::ToHex;
 use itertools::Itertools;
-use serde::Serialize;
-use sha3::{Digest, Keccak256};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    io::Write,
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+};
 use tinytemplate::{format_unescaped, TinyTemplate};
 
-static MODULE_TEMPLATE: &'static str = include_str!("../templates/ink-module.txt");
+/// Module is rendered section by section, so every function
+/// could be written to the output as soon as it is rendered.
+static TEMPLATES: &[(&str, &str)] = &[
+    ("constant", include_str!("../templates/ink-constant.txt")),
+    (
+        "selector_table",
+        include_str!("../templates/ink-selector-table.txt"),
+    ),
+    ("storage", include_str!("../templates/ink-storage.txt")),
+    ("crypto", include_str!("../templates/ink-crypto.txt")),
+    ("eip191", include_str!("../templates/ink-eip191.txt")),
+    ("event", include_str!("../templates/ink-event.txt")),
+    ("struct", include_str!("../templates/ink-struct.txt")),
+    (
+        "trait_definition",
+        include_str!("../templates/ink-trait-definition.txt"),
+    ),
+    (
+        "overloaded_args",
+        include_str!("../templates/ink-overloaded-args.txt"),
+    ),
+    (
+        "packed_args",
+        include_str!("../templates/ink-packed-args.txt"),
+    ),
+    ("impl", include_str!("../templates/ink-impl.txt")),
+    (
+        "account_address",
+        include_str!("../templates/ink-account-address.txt"),
+    ),
+    (
+        "caller_address",
+        include_str!("../templates/ink-caller-address.txt"),
+    ),
+    (
+        "router_storage",
+        include_str!("../templates/ink-router-storage.txt"),
+    ),
+    (
+        "router_impl",
+        include_str!("../templates/ink-router-impl.txt"),
+    ),
+    (
+        "overloaded_message",
+        include_str!("../templates/ink-overloaded-message.txt"),
+    ),
+    ("message", include_str!("../templates/ink-message.txt")),
+    ("epilogue", include_str!("../templates/ink-epilogue.txt")),
+    ("types", include_str!("../templates/ink-types.txt")),
+    (
+        "types_crate",
+        include_str!("../templates/ink-types-crate.txt"),
+    ),
+    (
+        "tests_prologue",
+        include_str!("../templates/ink-tests-prologue.txt"),
+    ),
+    (
+        "overloaded_test",
+        include_str!("../templates/ink-overloaded-test.txt"),
+    ),
+    ("test", include_str!("../templates/ink-test.txt")),
+    (
+        "tests_epilogue",
+        include_str!("../templates/ink-tests-epilogue.txt"),
+    ),
+    (
+        "e2e_prologue",
+        include_str!("../templates/ink-e2e-prologue.txt"),
+    ),
+    ("e2e_test", include_str!("../templates/ink-e2e-test.txt")),
+];
 
-#[derive(Serialize)]
+/// Templates specific to the target chain: how the module is set
+/// up and how the EVM call is performed. Target templates take
+/// precedence over the common ones with the same name.
+static XVM_TEMPLATES: &[(&str, &str)] = &[
+    ("prologue", include_str!("../templates/ink-prologue.txt")),
+    (
+        "dispatch",
+        include_str!("../templates/ink-xvm-dispatch.txt"),
+    ),
+    (
+        "raw_call",
+        include_str!("../templates/ink-xvm-raw-call.txt"),
+    ),
+    ("psp22", include_str!("../templates/ink-xvm-psp22.txt")),
+    ("psp34", include_str!("../templates/ink-xvm-psp34.txt")),
+    (
+        "call_error",
+        include_str!("../templates/ink-xvm-call-error.txt"),
+    ),
+    (
+        "custom_error",
+        include_str!("../templates/ink-xvm-custom-error.txt"),
+    ),
+    ("xvm_call", include_str!("../templates/ink-xvm-call.txt")),
+    (
+        "xvm_call_v2",
+        include_str!("../templates/ink-xvm-v2-call.txt"),
+    ),
+    (
+        "xvm_call_v3",
+        include_str!("../templates/ink-xvm-v3-call.txt"),
+    ),
+    (
+        "xvm_environment_v2",
+        include_str!("../templates/ink-xvm-v2-environment.txt"),
+    ),
+    (
+        "xvm_environment_v3",
+        include_str!("../templates/ink-xvm-v3-environment.txt"),
+    ),
+];
+
+static XCM_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "prologue",
+        include_str!("../templates/ink-xcm-prologue.txt"),
+    ),
+    (
+        "dispatch",
+        include_str!("../templates/ink-xcm-dispatch.txt"),
+    ),
+    (
+        "raw_call",
+        include_str!("../templates/ink-xcm-raw-call.txt"),
+    ),
+];
+
+/// Runtime module building `pallet_evm` calls instead of an ink! contract
+static FRONTIER_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "prologue",
+        include_str!("../templates/frontier-prologue.txt"),
+    ),
+    ("storage", include_str!("../templates/frontier-storage.txt")),
+    ("crypto", include_str!("../templates/frontier-crypto.txt")),
+    ("message", include_str!("../templates/frontier-message.txt")),
+    (
+        "overloaded_message",
+        include_str!("../templates/frontier-overloaded-message.txt"),
+    ),
+    (
+        "raw_call",
+        include_str!("../templates/frontier-raw-call.txt"),
+    ),
+    (
+        "epilogue",
+        include_str!("../templates/frontier-epilogue.txt"),
+    ),
+];
+
+/// Return values decoded into a tuple, limited by the tuples `Detokenize` is implemented for
+const MAX_OUTPUTS: usize = 8;
+
+/// Arguments above which the message hits the size of tuples
+/// that codec and type info traits ink! relies on are implemented for
+pub const MAX_ARGUMENTS: usize = 16;
+
+/// Size of the static buffer ink! decodes message input from
+const MAX_ENCODED_ARGUMENTS: usize = 16 * 1024;
+
+/// Topics of ink! events, the first topic of `DefaultEnvironment` is taken by the event itself
+const MAX_EVENT_TOPICS: usize = 3;
+
+/// Receiver hook of ERC-721 tokens sent with `safeTransferFrom`
+const ON_ERC721_RECEIVED: &str = "onERC721Received(address,address,uint256,bytes)";
+
+/// Functions of the ERC20 interface the PSP22 adapter forwards to
+const ERC20_FUNCTIONS: &[&str] = &[
+    "totalSupply()",
+    "balanceOf(address)",
+    "allowance(address,address)",
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+];
+
+/// Functions of the ERC721 interface the PSP34 adapter forwards to
+const ERC721_FUNCTIONS: &[&str] = &[
+    "balanceOf(address)",
+    "ownerOf(uint256)",
+    "approve(address,uint256)",
+    "getApproved(uint256)",
+    "setApprovalForAll(address,bool)",
+    "isApprovedForAll(address,address)",
+    "transferFrom(address,address,uint256)",
+];
+
+#[derive(Clone, Serialize)]
 struct Input {
     name: String,
 
@@ -19,38 +225,457 @@ struct Input {
 
     // Equivalent type to use in ink! code
     rust_type: String,
+
+    // Expression constructing `ethabi::ParamType` of the input
+    param_type: String,
+
+    // Type of the value decoded from the `Token` and the method doing so
+    token_type: String,
+    detokenize: String,
+
+    // Placeholder value in `cargo contract` argument syntax
+    example: String,
+
+    // Zero value of the type in Rust code used by the generated tests
+    // and its token, `None` if the type cannot be constructed there
+    zero_value: Option<String>,
+    #[serde(skip)]
+    zero_token: Token,
+
+    // Input is not a message argument, but the EVM address of the caller
+    from_caller: bool,
+
+    // Expression converting the argument to `Token`
+    tokenize: String,
+
+    // Size of the SCALE encoded argument with empty dynamic values
+    #[serde(skip)]
+    min_size: usize,
+}
+
+/// Identifier converted to all cases used in the templates
+#[derive(Serialize)]
+struct Identifier {
+    snake: String,
+    upper_snake: String,
+    upper_camel: String,
+}
+
+impl Identifier {
+    fn new(name: &str) -> Self {
+        Identifier {
+            snake: name.to_case(Case::Snake),
+            upper_snake: name.to_case(Case::UpperSnake),
+            upper_camel: name.to_case(Case::UpperCamel),
+        }
+    }
 }
 
+/// Function wrapped by a message of its own
 #[derive(Serialize)]
 pub struct Function {
     name: String,
+    ident: Rc<Identifier>,
     inputs: Vec<Input>,
+
+    /// Inputs taken by the message as arguments
+    arguments: Vec<Input>,
+
+    /// Arguments packed into the `{Name}Args` struct, which the message
+    /// takes instead when there are too many of them
+    packed: Vec<Input>,
+
+    /// Return type of the message and the value it is decoded from, if any
     output: String,
+    returns: Option<Output>,
+
+    /// Read-only messages take `&self`
+    is_view: bool,
     selector: String,
     selector_hash: String,
+    selector_bytes: String,
+
+    /// Gas used by the function according to the gas report
+    gas: Option<u64>,
+
+    /// Value in wei the function is called with by default
+    value: Option<String>,
+
+    /// Lines of the doc comment and example from the docs file
+    docs: Vec<String>,
+    example: Vec<String>,
+
+    /// Cargo feature the message is gated by
+    feature: Option<String>,
+
+    /// Storage field holding address of the called contract and its EVM ID
+    address_field: String,
+    evm_id: String,
+
+    /// Selector of the generated ink! message
+    message_selector: String,
+
+    /// Comma separated message selector bytes, i.e. `0xa9, 0x05, 0x9c, 0xbb`
+    message_selector_bytes: String,
+
+    /// Hex encoded calldata of the call with zero arguments, expected by the generated tests
+    test_input: Option<String>,
+}
+
+/// Value returned by the EVM function and decoded by the message,
+/// several values are decoded into a tuple
+#[derive(Serialize)]
+struct Output {
+    rust_type: String,
+
+    // Call of the `Detokenize` method decoding `output`
+    decode: String,
+
+    // Boolean results only tell whether the call succeeded
+    is_bool: bool,
+
+    // Hex encoded return data the message accepts, used by the generated tests
+    test_output: String,
+}
+
+/// EVM event mirrored by an ink! event
+#[derive(Serialize)]
+struct Event {
+    ident: Rc<Identifier>,
+    signature: String,
+    fields: Vec<EventField>,
+}
+
+#[derive(Serialize)]
+struct EventField {
+    name: String,
+    rust_type: String,
+    topic: bool,
+}
+
+/// Custom error of the ABI, a variant of the module error enum
+#[derive(Serialize)]
+struct CustomError {
+    ident: Rc<Identifier>,
+    signature: String,
+    selector_bytes: String,
+    fields: Vec<ErrorField>,
+}
+
+#[derive(Serialize)]
+struct ErrorField {
+    name: String,
+    rust_type: String,
+
+    // Expression constructing `ethabi::ParamType` of the field
+    param_type: String,
 }
 
 #[derive(Serialize)]
 struct Variant {
     inputs: Vec<Input>,
-    output: String,
+    arguments: Vec<Input>,
     selector: String,
     selector_hash: String,
+    selector_bytes: String,
+    gas: Option<u64>,
+    test_input: Option<String>,
 }
 
 #[derive(Serialize)]
 struct OverloadedFunction {
     name: String,
+    ident: Rc<Identifier>,
     variants: Vec<Variant>,
+
+    /// Selector ink! derives from the message name, see [`message_selector`]
+    message_selector: String,
+    message_selector_bytes: String,
+
+    /// Maximum gas of all variants
+    gas: Option<u64>,
+    value: Option<String>,
+
+    /// Always `None`, overloaded messages only report whether the call succeeded
+    returns: Option<Output>,
+
+    /// Whether all of the variants are read-only
+    is_view: bool,
+    docs: Vec<String>,
+    example: Vec<String>,
+    feature: Option<String>,
+    address_field: String,
+    evm_id: String,
 }
 
+/// Module level values of the generated code
 #[derive(Serialize)]
-struct Module {
+pub struct Module {
     #[serde(rename = "module_name")]
     name: String,
     evm_id: String,
+    para_id: u32,
+    namespace: Option<String>,
+
+    /// Storage fields holding addresses of the EVM contracts
+    address_fields: Vec<String>,
+
+    imports: Imports,
+    attributes: Vec<String>,
+    storage: Storage,
+    caller: Option<CallerAddress>,
+
+    /// Selector of `onERC721Received`, if the module wraps `safeTransferFrom`
+    erc721_received: Option<String>,
+
+    /// Hash of the ABI the module is generated from, see `sumi check`
+    abi_hash: String,
+
+    selector_table: bool,
+
+    /// Whether the ABI has no functions to wrap, so the module only exposes the raw call
+    stub: bool,
+
+    /// Whether to generate EIP-191 digest and signer recovery helpers
+    signature_helpers: bool,
+
+    /// Mapping of `account_address`, which messages taking idiomatic ink!
+    /// types and the PSP22 adapter convert accounts with
+    account_mapping: Option<CallerAddress>,
+
+    /// Name of the `#[ink::trait_definition]` the messages implement, if any
+    trait_name: Option<String>,
+
+    /// Whether messages return `Result` with `XvmCallError` instead of `bool` and `Option`
+    typed_errors: bool,
+
+    psp22: Option<Psp22>,
+    psp34: Option<Psp34>,
+
+    xvm: Xvm,
+    ink: InkCrates,
+    /// Crate the EVM types and conversions are imported from instead of being defined
+    shared_types: Option<String>,
+}
+
+/// PSP22 implementation forwarding to the ERC20 functions of the contract
+#[derive(Serialize)]
+struct Psp22 {
+    /// Storage field holding address of the token
+    address_field: String,
+}
+
+/// PSP34 implementation forwarding to the ERC721 functions of the contract
+#[derive(Serialize)]
+struct Psp34 {
+    address_field: String,
+
+    /// Whether the collection has `totalSupply()` of ERC721 enumerable extension
+    enumerable: bool,
+}
+
+/// Functions of the module prepared for rendering
+#[derive(Serialize)]
+pub struct Model {
+    module: Module,
     functions: Vec<Function>,
     overloaded_functions: Vec<OverloadedFunction>,
+    events: Vec<Event>,
+    structs: Vec<Struct>,
+
+    /// Custom errors decoded by the module error enum, only generated with typed errors
+    errors: Vec<CustomError>,
+
+    #[serde(skip)]
+    warnings: Vec<Warning>,
+}
+
+impl Module {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Hex encoded hash of the ABI the module is generated from
+    pub fn abi_hash(&self) -> &str {
+        &self.abi_hash
+    }
+}
+
+impl Function {
+    /// Name of the function in the ABI
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Canonical signature, i.e. `transfer(address,uint256)`
+    pub fn signature(&self) -> &str {
+        &self.selector
+    }
+
+    /// Name of the generated message
+    pub fn message(&self) -> &str {
+        &self.ident.snake
+    }
+
+    /// Hex encoded selector of the generated message
+    pub fn message_selector(&self) -> &str {
+        &self.message_selector
+    }
+
+    pub fn is_view(&self) -> bool {
+        self.is_view
+    }
+}
+
+impl Model {
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Functions with messages of their own, overloaded ones are not included
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// Number of generated messages, overloaded functions share one
+    pub fn message_count(&self) -> usize {
+        self.functions.len() + self.overloaded_functions.len()
+    }
+
+    /// Non-fatal findings of the generation, i.e. skipped functions
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Functions taking their arguments packed into a struct
+    fn packed_functions(&self) -> Vec<&Function> {
+        self.functions
+            .iter()
+            .filter(|function| !function.packed.is_empty())
+            .collect()
+    }
+}
+
+/// Single contract dispatching namespaced messages to several EVM contracts
+#[derive(Serialize)]
+struct Router<'a> {
+    module_name: &'a str,
+    evm_id: &'a str,
+    para_id: u32,
+    imports: &'a Imports,
+    attributes: &'a [String],
+    storage: &'a Storage,
+    caller: Option<CallerAddress>,
+    account_mapping: Option<CallerAddress>,
+
+    /// Always `None`, routers implement no trait
+    trait_name: Option<String>,
+
+    /// Always `None`, routers define the EVM types themselves
+    shared_types: Option<String>,
+    targets: Vec<&'a Module>,
+    xvm: Xvm,
+    ink: &'a InkCrates,
+}
+
+/// Context of the crate defining the EVM types for the modules of a workspace
+#[derive(Serialize)]
+struct SharedTypes<'a> {
+    shared_types: Option<&'a str>,
+    ink: &'a InkCrates,
+}
+
+/// Selectors of all functions, rendered at once
+#[derive(Serialize)]
+struct SelectorTable<'a> {
+    module: &'a Module,
+    functions: &'a [Function],
+}
+
+/// Context of an event section
+#[derive(Serialize)]
+struct EventChunk<'a> {
+    module: &'a Module,
+    event: &'a Event,
+}
+
+/// Context of a function level section
+#[derive(Serialize)]
+struct Chunk<'a, F> {
+    module: &'a Module,
+    function: &'a F,
+}
+
+/// Rust struct of tuple parameters declared as a Solidity struct
+#[derive(Serialize)]
+struct Struct {
+    name: String,
+    fields: Vec<StructField>,
+
+    /// Whether the struct is decoded from return data, so it needs `Detokenize`
+    decodes: bool,
+}
+
+#[derive(Serialize)]
+struct StructField {
+    name: String,
+    rust_type: String,
+
+    // Expression converting the field of `self` to `Token`
+    tokenize: String,
+}
+
+/// Structs of the parameters in the order they are first used, each defined once
+#[derive(Default)]
+struct Structs {
+    defined: Vec<Struct>,
+
+    /// Rust names keyed by the internal type, i.e. `Exchange.Order`
+    names: HashMap<String, String>,
+    decodes: bool,
+}
+
+/// Canonical type of the parameter as used in signatures. Struct parameters are
+/// `tuple` in the ABI and expand to their components, i.e. `(address,uint256)[]`
+pub fn canonical_type(param: &json::JsonValue) -> String {
+    let ty = param["type"].as_str().unwrap_or_default();
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => format!(
+            "({}){suffix}",
+            param["components"].members().map(canonical_type).join(",")
+        ),
+        None => ty.to_owned(),
+    }
+}
+
+/// Canonical signature of the function or event, i.e. `transfer(address,uint256)`
+pub fn signature(item: &json::JsonValue) -> String {
+    format!(
+        "{}({})",
+        item["name"].as_str().unwrap_or_default(),
+        item["inputs"].members().map(canonical_type).join(",")
+    )
+}
+
+/// Keccak256 hash of the event signature
+pub fn topic(signature: &str) -> [u8; 32] {
+    hash::topic(&Keccak, signature)
+}
+
+/// First 4 bytes of Keccak256 hash of the signature
+pub fn selector(signature: &str) -> [u8; 4] {
+    hash::selector(&Keccak, signature)
+}
+
+/// Selector ink! derives for a message without an explicit one, the first 4 bytes
+/// of BLAKE2b-256 hash of the message name, i.e. `Erc20Like::transfer` in a trait
+pub fn message_selector(name: &str) -> [u8; 4] {
+    let hash = Blake2b::<U32>::digest(name.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn selector_bytes(selector: &[u8; 4]) -> String {
+    selector.iter().map(|b| format!("{b:#04x}")).join(", ")
 }
 
 fn convert_type(ty: &ParamType) -> String {
@@ -64,199 +689,3073 @@ fn convert_type(ty: &ParamType) -> String {
         ParamType::Bytes => "Vec<u8>".to_owned(),
         ParamType::String => "String".to_owned(),
 
-        ParamType::Int(size) => match size {
-            8 => "i8",
-            16 => "i16",
-            32 => "i32",
-            64 => "i64",
-            128 => "i128",
+        ParamType::Int(size) => match native_width(*size) {
+            Some(width) => format!("i{width}"),
+            None => "I256".to_owned(),
+        },
 
-            _ => "I256",
-        }
-        .to_owned(),
+        ParamType::Uint(size) => match native_width(*size) {
+            Some(width) => format!("u{width}"),
+            None => "U256".to_owned(),
+        },
+    }
+}
 
-        ParamType::Uint(size) => match size {
-            8 => "u8",
-            16 => "u16",
-            32 => "u32",
-            64 => "u64",
-            128 => "u128",
+/// Width of the smallest native integer holding values of the given size,
+/// i.e. `uint24` is taken as `u32`. Sizes above 128 bits need 256-bit types.
+fn native_width(size: usize) -> Option<usize> {
+    [8, 16, 32, 64, 128]
+        .into_iter()
+        .find(|&width| size <= width)
+}
 
-            _ => "U256",
+/// Expression constructing `ethabi::ParamType` of the type
+fn param_type_expr(ty: &ParamType) -> String {
+    match ty {
+        ParamType::Address => "ParamType::Address".to_owned(),
+        ParamType::Bytes => "ParamType::Bytes".to_owned(),
+        ParamType::Bool => "ParamType::Bool".to_owned(),
+        ParamType::String => "ParamType::String".to_owned(),
+        ParamType::Int(size) => format!("ParamType::Int({size})"),
+        ParamType::Uint(size) => format!("ParamType::Uint({size})"),
+        ParamType::FixedBytes(size) => format!("ParamType::FixedBytes({size})"),
+        ParamType::Array(inner) => {
+            format!("ParamType::Array(Box::new({}))", param_type_expr(inner))
         }
-        .to_owned(),
+        ParamType::FixedArray(inner, size) => format!(
+            "ParamType::FixedArray(Box::new({}), {size})",
+            param_type_expr(inner)
+        ),
+        ParamType::Tuple(inner) => format!(
+            "ParamType::Tuple(vec![{}])",
+            inner.iter().map(param_type_expr).join(", ")
+        ),
     }
 }
 
-pub fn render(json: json::JsonValue, module_name: &str, evm_id: &str) -> Result<String, Error> {
-    let mut template = TinyTemplate::new();
+/// Rust type of the decoded `ethabi::Token` and the method extracting it
+fn token_type(ty: &ParamType) -> (&'static str, &'static str) {
+    match ty {
+        ParamType::Address => ("ethabi::Address", "into_address"),
+        ParamType::Bytes => ("Vec<u8>", "into_bytes"),
+        ParamType::Bool => ("bool", "into_bool"),
+        ParamType::String => ("String", "into_string"),
+        ParamType::Int(_) => ("ethabi::Int", "into_int"),
+        ParamType::Uint(_) => ("ethabi::Uint", "into_uint"),
+        ParamType::FixedBytes(_) => ("Vec<u8>", "into_fixed_bytes"),
+        ParamType::Array(_) => ("Vec<Token>", "into_array"),
+        ParamType::FixedArray(..) => ("Vec<Token>", "into_fixed_array"),
+        ParamType::Tuple(_) => ("Vec<Token>", "into_tuple"),
+    }
+}
 
-    template.set_default_formatter(&format_unescaped);
-    template.add_template("module", MODULE_TEMPLATE)?;
+/// Placeholder value of the type in `cargo contract` argument syntax
+fn example(ty: &ParamType) -> String {
+    match ty {
+        ParamType::Bool => "false".to_owned(),
+        ParamType::Address => format!("H160(0x{})", "00".repeat(20)),
+        ParamType::FixedBytes(size) => format!("FixedBytes(0x{})", "00".repeat(*size)),
+        ParamType::Bytes => "0x".to_owned(),
+        ParamType::String => "\"\"".to_owned(),
+        ParamType::Array(_) => "[]".to_owned(),
+        ParamType::FixedArray(inner, size) => {
+            format!("[{}]", vec![example(inner); *size].join(", "))
+        }
+        ParamType::Tuple(inner) => format!("({})", inner.iter().map(example).join(", ")),
 
-    template.add_formatter("snake", |value, buffer| match value {
-        serde_json::Value::String(s) => {
-            buffer.push_str(&s.to_case(Case::Snake));
-            Ok(())
+        ParamType::Int(size) | ParamType::Uint(size) if native_width(*size).is_some() => {
+            "0".to_owned()
         }
-        _ => Err(tinytemplate::error::Error::GenericError {
-            msg: "string value expected".to_owned(),
-        }),
-    });
 
-    template.add_formatter("upper_snake", |value, buffer| match value {
-        serde_json::Value::String(s) => {
-            buffer.push_str(&s.to_case(Case::UpperSnake));
-            Ok(())
+        ParamType::Int(_) => format!("I256(0x{})", "00".repeat(32)),
+        ParamType::Uint(_) => format!("U256(0x{})", "00".repeat(32)),
+    }
+}
+
+/// Size of the SCALE encoded value of the type, when dynamic values are empty
+fn min_encoded_size(ty: &ParamType) -> usize {
+    match ty {
+        ParamType::Bool => 1,
+        ParamType::Address => 20,
+        ParamType::FixedBytes(size) => *size,
+        // Compact encoded zero length
+        ParamType::Bytes | ParamType::String | ParamType::Array(_) => 1,
+        ParamType::FixedArray(inner, size) => min_encoded_size(inner) * size,
+        ParamType::Tuple(inner) => inner.iter().map(min_encoded_size).sum(),
+        ParamType::Int(size) | ParamType::Uint(size) => native_width(*size).unwrap_or(256) / 8,
+    }
+}
+
+/// Zero value of the type in Rust code of the generated module
+fn zero_value(ty: &ParamType) -> Option<String> {
+    Some(match ty {
+        ParamType::Bool => "false".to_owned(),
+        ParamType::Address => "H160::from([0u8; 20])".to_owned(),
+        ParamType::FixedBytes(size) => format!("FixedBytes([0u8; {size}])"),
+        ParamType::Bytes => "Vec::new()".to_owned(),
+        ParamType::String => "String::new()".to_owned(),
+        ParamType::Array(inner) => {
+            zero_value(inner)?;
+            "Vec::new()".to_owned()
         }
-        _ => Err(tinytemplate::error::Error::GenericError {
-            msg: "string value expected".to_owned(),
-        }),
-    });
+        ParamType::FixedArray(inner, size) => {
+            format!("[(); {size}].map(|_| {})", zero_value(inner)?)
+        }
+        // Single element tuple is converted to the element type itself
+        ParamType::Tuple(inner) if inner.len() == 1 => return None,
+        ParamType::Tuple(inner) => format!(
+            "({})",
+            inner
+                .iter()
+                .map(zero_value)
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
 
-    template.add_formatter("upper_camel", |value, buffer| match value {
-        serde_json::Value::String(s) => {
-            buffer.push_str(&s.to_case(Case::UpperCamel));
-            Ok(())
+        ParamType::Int(size) | ParamType::Uint(size) if native_width(*size).is_some() => {
+            "0".to_owned()
         }
-        _ => Err(tinytemplate::error::Error::GenericError {
-            msg: "string value expected".to_owned(),
-        }),
-    });
 
-    template.add_formatter("capitalize", |value, buffer| match value {
-        serde_json::Value::String(s) => {
-            let (head, tail) = s.split_at(1);
+        ParamType::Int(_) => "I256::from([0u8; 32])".to_owned(),
+        ParamType::Uint(_) => "U256::from([0u8; 32])".to_owned(),
+    })
+}
+
+/// Whether the type contains `bytes` anywhere
+fn contains_bytes(ty: &ParamType) -> bool {
+    match ty {
+        ParamType::Bytes => true,
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => contains_bytes(inner),
+        ParamType::Tuple(inner) => inner.iter().any(contains_bytes),
+        _ => false,
+    }
+}
 
-            buffer.push_str(&head.to_uppercase());
-            buffer.push_str(tail);
+/// Expression converting the value of the type to `Token`.
+/// `bytes` are `Vec<u8>` in the module, which `Tokenize` would
+/// turn into an array of integers, so they are wrapped explicitly.
+fn tokenize_expr(ty: &ParamType, value: &str) -> String {
+    if !contains_bytes(ty) {
+        return format!("{value}.tokenize()");
+    }
 
-            Ok(())
+    match ty {
+        ParamType::Bytes => format!("Token::Bytes({value})"),
+        ParamType::Array(inner) => format!(
+            "Token::Array({value}.into_iter().map(|item| {}).collect())",
+            tokenize_expr(inner, "item")
+        ),
+        ParamType::FixedArray(inner, _) => format!(
+            "Token::FixedArray({value}.into_iter().map(|item| {}).collect())",
+            tokenize_expr(inner, "item")
+        ),
+        // Single element tuple is converted to the element type itself
+        ParamType::Tuple(inner) if inner.len() == 1 => {
+            format!("Token::Tuple(vec![{}])", tokenize_expr(&inner[0], value))
         }
-        _ => Err(tinytemplate::error::Error::GenericError {
-            msg: "string value expected".to_owned(),
-        }),
-    });
+        ParamType::Tuple(inner) => {
+            let names = (0..inner.len()).map(|i| format!("item{i}")).collect_vec();
+            format!(
+                "{{ let ({}) = {value}; Token::Tuple(vec![{}]) }}",
+                names.join(", "),
+                inner
+                    .iter()
+                    .zip(&names)
+                    .map(|(ty, name)| tokenize_expr(ty, name))
+                    .join(", ")
+            )
+        }
+        _ => unreachable!("only types containing bytes are wrapped"),
+    }
+}
 
-    let mut is_overloaded = HashMap::new();
-    for (index, function) in json
-        .members()
-        .enumerate()
-        .filter(|(_, item)| item["type"] == "function")
-        .filter(|(_, item)| item["stateMutability"] != "view")
-        .filter(|(_, item)| {
-            item["outputs"]
-                .members()
-                .all(|output| output["type"] == "bool")
-        })
-    {
-        let function_name = function["name"].as_str().ok_or_else(|| {
-            Error::Metadata(format!("'name' for ABI item {index} not exists or is not a string"))
-        })?;
+/// Input taking an idiomatic ink! type instead of the EVM one, converted to the
+/// `Token` by the message: `AccountId` for addresses, `Balance` for integers wider
+/// than the native ones and byte arrays for fixed bytes. Other inputs are unchanged.
+fn native_input(input: Input, ty: &ParamType, mapping: CallerAddress) -> Input {
+    let name = &input.name;
+    match ty {
+        ParamType::Address => Input {
+            rust_type: "AccountId".to_owned(),
+            tokenize: format!("account_address(&{name}).tokenize()"),
+            // The zero account
+            example: "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM".to_owned(),
+            // Hash of the zero account is not the zero address the tests expect
+            zero_value: (!mapping.hashed).then(|| "AccountId::from([0u8; 32])".to_owned()),
+            min_size: 32,
+            ..input
+        },
+        ParamType::Uint(size) if native_width(*size).is_none() => Input {
+            rust_type: "Balance".to_owned(),
+            tokenize: format!("Token::Uint({name}.into())"),
+            example: "0".to_owned(),
+            zero_value: Some("0".to_owned()),
+            min_size: 16,
+            ..input
+        },
+        ParamType::FixedBytes(size) => Input {
+            rust_type: format!("[u8; {size}]"),
+            tokenize: format!("Token::FixedBytes({name}.to_vec())"),
+            example: format!("0x{}", "00".repeat(*size)),
+            zero_value: Some(format!("[0u8; {size}]")),
+            min_size: *size,
+            ..input
+        },
+        _ => input,
+    }
+}
 
-        is_overloaded
-            .entry(function_name)
-            .and_modify(|v| *v = true)
-            .or_insert(false);
+/// Size of the Rust integer type, including the `Balance` alias of `u128`
+fn int_size(rust_type: &str, signed: bool) -> Option<usize> {
+    if rust_type == "Balance" {
+        return (!signed).then_some(16);
     }
 
-    let mut overloaded_functions = Vec::<OverloadedFunction>::new();
-    let mut functions = Vec::new();
+    let bits: usize = rust_type
+        .strip_prefix(if signed { 'i' } else { 'u' })?
+        .parse()
+        .ok()?;
 
-    for (index, function) in json
-        .members()
-        .enumerate()
-        .filter(|(_, item)| item["type"] == "function")
-        .filter(|(_, item)| item["stateMutability"] != "view")
-        .filter(|(_, item)| {
-            item["outputs"]
-                .members()
-                .all(|output| output["type"] == "bool")
-        })
-    {
-        let function_name = function["name"].as_str().ok_or_else(|| {
-            Error::Metadata(format!("'name' for ABI item {index} not exists or is not a string"))
-        })?;
+    [8, 16, 32, 64, 128].contains(&bits).then_some(bits / 8)
+}
 
-        let inputs = function["inputs"]
-            .members()
-            .enumerate()
-            .map(|(index, input)| {
-                let name = input["name"].as_str().ok_or_else(|| {
-                    Error::Metadata(format!("invalid 'name' input parameter {index} of function {function_name}"))
-                })?;
-
-                let raw_type = input["type"].as_str().ok_or_else(|| {
-                    Error::Metadata(format!("invalid 'type' in input parameter item {name} ({index}) of function {function_name}"))
-                })?;
-
-                let param_type = ethabi::param_type::Reader::read(raw_type)?;
-                let converted = convert_type(&param_type);
-
-                Ok(Input {
-                    name: name.to_owned(),
-                    evm_type: raw_type.to_owned(),
-                    rust_type: converted,
-                })
+/// Input taking the Rust type the user mapped the EVM type to, converted at the call
+/// site. Integers may be narrower than the EVM type when the values are known to fit.
+fn override_input(
+    input: Input,
+    ty: &ParamType,
+    rust_type: &str,
+    mapping: CallerAddress,
+) -> Option<Input> {
+    let name = &input.name;
+    match ty {
+        _ if input.rust_type == rust_type => Some(input),
+        ParamType::Address if rust_type == "AccountId" => Some(native_input(input, ty, mapping)),
+        ParamType::FixedBytes(size) if rust_type == format!("[u8; {size}]") => {
+            Some(native_input(input, ty, mapping))
+        }
+        ParamType::Uint(_) | ParamType::Int(_) => {
+            let signed = matches!(ty, ParamType::Int(_));
+            let size = int_size(rust_type, signed)?;
+            Some(Input {
+                rust_type: rust_type.to_owned(),
+                tokenize: if signed {
+                    format!("{name}.tokenize()")
+                } else {
+                    format!("Token::Uint({name}.into())")
+                },
+                example: "0".to_owned(),
+                zero_value: Some("0".to_owned()),
+                min_size: size,
+                ..input
             })
-            .collect::<Result<Vec<Input>, Error>>()?;
+        }
+        _ => None,
+    }
+}
 
-        // let outputs: String = function["outputs"].members().map(|m| format!("{}: {}, ", m["name"], m["type"])).collect();
+/// Internal type of the tuple parameter or array of them declared as a struct,
+/// i.e. `Exchange.Order` for `struct Exchange.Order[]`
+fn struct_type(param: &json::JsonValue) -> Option<&str> {
+    if !param["type"].as_str()?.starts_with("tuple") {
+        return None;
+    }
 
-        let selector = format!(
-            "{function_name}({args})",
-            args = inputs.iter().map(|input| input.evm_type.as_str()).join(","),
-        );
+    let internal = param["internalType"].as_str()?.strip_prefix("struct ")?;
+    internal.split('[').next()
+}
 
-        let mut hasher = Keccak256::new();
-        hasher.update(selector.as_bytes());
-        let selector_hash: &[u8] = &hasher.finalize();
-        let selector_hash: [u8; 4] = selector_hash[0..=3]
-            .try_into()
-            .expect("Keccac256 hash should contain at least 4 bytes");
-
-        if is_overloaded[function_name] {
-            let function = {
-                if let Some(function) = overloaded_functions
-                    .iter_mut()
-                    .find(|f| f.name == function_name)
-                {
-                    function
-                } else {
-                    overloaded_functions.push(OverloadedFunction {
-                        name: function_name.to_owned(),
-                        variants: Vec::new(),
-                    });
+/// Struct field named after the component, i.e. `amountIn` -> `amount_in`
+fn field_name(component: &json::JsonValue, index: usize) -> String {
+    match component["name"].as_str() {
+        Some(name) if !name.is_empty() => ident::field(&name.to_case(Case::Snake)),
+        _ => format!("field_{index}"),
+    }
+}
 
-                    overloaded_functions
-                        .last_mut()
-                        .expect("we've just pushed an item; cannot fail")
-                }
-            };
+/// Expression converting the parameter to `Token`. Structs and arrays
+/// of them are converted by the `Tokenize` implementation of the struct.
+fn tokenize_param(param: &json::JsonValue, ty: &ParamType, value: &str) -> String {
+    match struct_type(param) {
+        Some(_) => format!("{value}.tokenize()"),
+        None => tokenize_expr(ty, value),
+    }
+}
 
-            function.variants.push(Variant {
-                inputs,
-                output: "bool".to_owned(), // TODO
-                selector,
-                selector_hash: selector_hash.encode_hex(),
-            })
-        } else {
-            functions.push(Function {
-                name: function_name.to_owned(),
-                inputs,
-                output: "bool".to_owned(), // TODO
-                selector,
-                selector_hash: selector_hash.encode_hex(),
-            });
+impl Structs {
+    /// Rust type of the parameter, where tuples declared as structs are named
+    /// structs and anything else is the same as in [`convert_type`]
+    fn rust_type(&mut self, param: &json::JsonValue, ty: &ParamType) -> String {
+        let internal = match struct_type(param) {
+            Some(internal) => internal,
+            None => return convert_type(ty),
+        };
+
+        match ty {
+            ParamType::Array(inner) => format!("Vec<{}>", self.rust_type(param, inner)),
+            ParamType::FixedArray(inner, size) => {
+                format!("[{}; {size}]", self.rust_type(param, inner))
+            }
+            ParamType::Tuple(components) => self.define(internal, param, components),
+            _ => convert_type(ty),
         }
     }
 
-    let module = Module {
-        name: module_name.to_owned(),
-        evm_id: evm_id.to_owned(),
-        overloaded_functions,
-        functions,
-    };
+    /// Name of the struct, defining it on the first use. Structs of different contracts
+    /// sharing the name are qualified with the contract, i.e. `ExchangeOrder`.
+    fn define(
+        &mut self,
+        internal: &str,
+        param: &json::JsonValue,
+        components: &[ParamType],
+    ) -> String {
+        if let Some(name) = self.names.get(internal) {
+            return name.clone();
+        }
+
+        let short = internal.rsplit('.').next().unwrap_or(internal);
+        let mut name = short.to_case(Case::UpperCamel);
+        if self.names.values().any(|other| *other == name) {
+            name = internal.replace('.', "_").to_case(Case::UpperCamel);
+        }
+        self.names.insert(internal.to_owned(), name.clone());
+
+        let fields = param["components"]
+            .members()
+            .zip(components)
+            .enumerate()
+            .map(|(index, (component, ty))| {
+                let field = field_name(component, index);
+                StructField {
+                    rust_type: self.rust_type(component, ty),
+                    tokenize: tokenize_param(component, ty, &format!("self.{field}")),
+                    name: field,
+                }
+            })
+            .collect();
+
+        self.defined.push(Struct {
+            name: name.clone(),
+            fields,
+            decodes: self.decodes,
+        });
+
+        name
+    }
 
-    Ok(template.render("module", &module)?)
+    /// Placeholder value of the parameter in `cargo contract` argument syntax
+    fn example(&self, param: &json::JsonValue, ty: &ParamType) -> String {
+        let name = match struct_type(param).and_then(|internal| self.names.get(internal)) {
+            Some(name) => name,
+            None => return example(ty),
+        };
+
+        match ty {
+            ParamType::FixedArray(inner, size) => {
+                format!("[{}]", vec![self.example(param, inner); *size].join(", "))
+            }
+            ParamType::Tuple(components) => format!(
+                "{name} {{ {} }}",
+                param["components"]
+                    .members()
+                    .zip(components)
+                    .enumerate()
+                    .map(|(index, (component, ty))| format!(
+                        "{}: {}",
+                        field_name(component, index),
+                        self.example(component, ty)
+                    ))
+                    .join(", ")
+            ),
+            _ => example(ty),
+        }
+    }
+}
+
+/// Token of the zero value of the type
+fn zero_token(ty: &ParamType) -> Token {
+    match ty {
+        ParamType::Bool => Token::Bool(false),
+        ParamType::Address => Token::Address(Default::default()),
+        ParamType::FixedBytes(size) => Token::FixedBytes(vec![0; *size]),
+        ParamType::Bytes => Token::Bytes(Vec::new()),
+        ParamType::String => Token::String(String::new()),
+        ParamType::Array(_) => Token::Array(Vec::new()),
+        ParamType::FixedArray(inner, size) => Token::FixedArray(vec![zero_token(inner); *size]),
+        ParamType::Tuple(inner) => Token::Tuple(inner.iter().map(zero_token).collect()),
+        ParamType::Int(_) => Token::Int(Default::default()),
+        ParamType::Uint(_) => Token::Uint(Default::default()),
+    }
+}
+
+/// Calldata of the call with zero arguments, if all of them could be constructed in tests
+fn test_input(selector: &[u8; 4], inputs: &[Input]) -> Option<String> {
+    // Caller address depends on the account mapping and is not checked by tests
+    if inputs
+        .iter()
+        .any(|input| input.zero_value.is_none() || input.from_caller)
+    {
+        return None;
+    }
+
+    let tokens = inputs
+        .iter()
+        .map(|input| input.zero_token.clone())
+        .collect_vec();
+
+    let mut calldata = selector.to_vec();
+    calldata.extend(ethabi::encode(&tokens));
+    Some(calldata.encode_hex())
+}
+
+/// Name, canonical type and parsed type of the input parameter
+fn parse_param(
+    function: &str,
+    index: usize,
+    input: &json::JsonValue,
+) -> Result<(String, String, ParamType), ParamError> {
+    let malformed = |reason: &str| ParamError::Malformed {
+        function: function.to_owned(),
+        index,
+        reason: reason.to_owned(),
+    };
+
+    let name = input["name"]
+        .as_str()
+        .ok_or_else(|| malformed("'name' is missing or not a string"))?;
+
+    if !input["type"].is_string() {
+        return Err(malformed("'type' is missing or not a string"));
+    }
+
+    // Selectors are computed over the expanded tuple components
+    let ty = canonical_type(input);
+    match ethabi::param_type::Reader::read(&ty) {
+        Ok(param_type) => Ok((name.to_owned(), ty, param_type)),
+        Err(_) => Err(ParamError::Unsupported {
+            function: function.to_owned(),
+            index,
+            ty,
+        }),
+    }
+}
+
+/// Inputs the message takes as arguments, all but the caller address
+fn arguments(inputs: &[Input]) -> Vec<Input> {
+    inputs
+        .iter()
+        .filter(|input| !input.from_caller)
+        .cloned()
+        .collect()
+}
+
+/// Single `args` argument of the struct the arguments are packed into
+fn packed_argument(ident: &Identifier, arguments: &[Input]) -> Input {
+    let rust_type = format!("{}Args", ident.upper_camel);
+    let zero_value = arguments
+        .iter()
+        .map(|input| Some(format!("{}: {}", input.name, input.zero_value.as_ref()?)))
+        .collect::<Option<Vec<_>>>()
+        .map(|fields| format!("{rust_type} {{ {} }}", fields.join(", ")));
+
+    Input {
+        name: "args".to_owned(),
+        evm_type: format!(
+            "({})",
+            arguments
+                .iter()
+                .map(|input| input.evm_type.as_str())
+                .join(",")
+        ),
+        param_type: format!(
+            "ParamType::Tuple(vec![{}])",
+            arguments
+                .iter()
+                .map(|input| input.param_type.as_str())
+                .join(", ")
+        ),
+        token_type: "Vec<Token>".to_owned(),
+        detokenize: "into_tuple".to_owned(),
+        example: format!(
+            "{rust_type} {{ {} }}",
+            arguments
+                .iter()
+                .map(|input| format!("{}: {}", input.name, input.example))
+                .join(", ")
+        ),
+        zero_value,
+        zero_token: Token::Tuple(
+            arguments
+                .iter()
+                .map(|input| input.zero_token.clone())
+                .collect(),
+        ),
+        from_caller: false,
+        // Message destructures the struct, so the fields are tokenized one by one
+        tokenize: String::new(),
+        min_size: arguments.iter().map(|input| input.min_size).sum(),
+        rust_type,
+    }
+}
+
+/// Options of module generation
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub module_name: String,
+    pub evm_id: String,
+
+    /// Parachain the EVM contract lives on, used by the XCM target
+    pub para_id: u32,
+
+    /// Selects functions to generate; entries filtered out
+    /// are skipped before any processing takes place.
+    pub filter: Filter,
+
+    /// Prefix of message names, used when several
+    /// contracts are wrapped by a single router
+    pub namespace: Option<String>,
+
+    /// Gas estimates attached to the generated messages
+    pub gas_report: Option<Arc<gas::Report>>,
+
+    /// Curated documentation of the generated messages
+    pub docs: Option<Arc<docs::Docs>>,
+
+    /// Cargo features gating functions selected by the filters
+    pub features: Vec<(String, Filter)>,
+
+    /// Groups of functions calling other contracts than the default one
+    pub target_groups: Vec<TargetGroup>,
+
+    pub imports: Imports,
+
+    /// Inner attributes of the module, i.e. `allow(clippy::too_many_arguments)`
+    pub attributes: Vec<String>,
+
+    pub storage: Storage,
+
+    /// Address argument filled with the EVM address of the message caller
+    pub caller: Option<CallerForwarding>,
+
+    pub missing_mutability: MissingMutability,
+
+    /// Whether selectors are kept in a single table instead of a constant per function
+    pub selector_table: bool,
+
+    /// Whether view and pure functions are wrapped as read-only messages
+    pub include_views: bool,
+
+    /// Value and gas of the calls, keyed by function name
+    pub call_defaults: BTreeMap<String, CallDefaults>,
+
+    /// Whether to generate a stub instead of failing when no functions are left to wrap
+    pub allow_empty: bool,
+
+    /// Whether to generate EIP-191 personal message helpers, hashing with the target backend
+    pub signature_helpers: bool,
+
+    /// Whether messages take `AccountId`, `Balance` and byte arrays instead of the
+    /// EVM types, with the mapping deriving EVM addresses from the accounts
+    pub native_types: Option<CallerAddress>,
+
+    pub overloads: Overloads,
+
+    /// Whether messages implement an `#[ink::trait_definition]` of the interface
+    pub trait_definition: bool,
+
+    /// Whether messages return `Result<_, XvmCallError>` telling why the call failed,
+    /// only supported by the targets decoding results of the calls
+    pub typed_errors: bool,
+
+    /// Account mapping of the PSP22 trait implementation forwarding to ERC20
+    /// functions, if the module implements it
+    pub psp22: Option<CallerAddress>,
+
+    /// Same for PSP34 forwarding to ERC721 functions
+    pub psp34: Option<CallerAddress>,
+
+    /// Shape of `xvm_call` the astar-xvm target calls
+    pub xvm_version: XvmVersion,
+
+    /// Whether the module keeps the gas limit of the calls in storage, set by the account that
+    /// instantiated it. Only forwarded by the XVM versions taking one.
+    pub gas_limit_setter: bool,
+
+    pub ink: InkCrates,
+
+    /// Crate of the workspace defining the EVM types, which the module imports
+    pub shared_types: Option<String>,
+
+    pub type_overrides: TypeOverrides,
+}
+
+/// Paths of the ink! crates, which ink! 4 merged into the single `ink` crate
+#[derive(Debug, Clone, Serialize)]
+pub struct InkCrates {
+    /// Whether ink! comes as separate crates, i.e. `ink_lang` and `ink_env` of ink! 3
+    pub separate: bool,
+    pub env: &'static str,
+    pub prelude: &'static str,
+
+    /// Whether chain extensions are declared with an extension ID and function IDs, since ink! 5
+    pub function_ids: bool,
+
+    /// Whether `ink_e2e` is available, since ink! 4
+    pub e2e: bool,
+
+    /// Whether e2e tests submit builders of the calls to the client, since ink! 5
+    pub e2e_builders: bool,
+}
+
+impl InkCrates {
+    /// Crates of the major version of ink!, `None` if the templates are not written for it
+    pub fn of(major: u64) -> Option<Self> {
+        match major {
+            3 => Some(InkCrates {
+                separate: true,
+                env: "ink_env",
+                prelude: "ink_prelude",
+                function_ids: false,
+                e2e: false,
+                e2e_builders: false,
+            }),
+            4 | 5 => Some(InkCrates {
+                separate: false,
+                env: "ink::env",
+                prelude: "ink::prelude",
+                function_ids: major == 5,
+                e2e: true,
+                e2e_builders: major == 5,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Default for InkCrates {
+    fn default() -> Self {
+        InkCrates::of(3).expect("templates are written for ink! 3; cannot fail")
+    }
+}
+
+/// `use` statements of the generated module
+#[derive(Debug, Clone, Serialize)]
+pub struct Imports {
+    /// Whether to import everything the default templates rely on
+    pub default: bool,
+
+    /// Additional paths to import, i.e. `ink_prelude::collections::BTreeMap`
+    pub extra: Vec<String>,
+}
+
+/// Paths the default templates of ink! targets import, which extra imports must not repeat
+pub const DEFAULT_IMPORTS: &[&str] = &[
+    "ethabi::ParamType",
+    "ethabi::Token",
+    "hex_literal::hex",
+    "ink_prelude::string::String",
+    "ink_prelude::vec::Vec",
+    "ink::prelude::string::String",
+    "ink::prelude::vec::Vec",
+    "scale::Decode",
+    "scale::Encode",
+    "scale_info::TypeInfo",
+];
+
+impl Default for Imports {
+    fn default() -> Self {
+        Imports {
+            default: true,
+            extra: Vec::new(),
+        }
+    }
+}
+
+/// Additions to the `#[ink(storage)]` struct of the module
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Storage {
+    pub derives: Vec<String>,
+
+    /// Contents of the attributes, i.e. `cfg_attr(feature = "std", derive(Debug))`
+    pub attributes: Vec<String>,
+
+    /// Whether addresses are wrapped in `ink_storage::Lazy`
+    pub lazy: bool,
+}
+
+/// Value and gas the function is called with unless given otherwise
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallDefaults {
+    /// Value in wei
+    pub value: Option<u128>,
+    pub gas: Option<u64>,
+}
+
+/// How functions of hand-written ABIs missing the state mutability are treated
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingMutability {
+    /// Wrapped as state modifying functions
+    #[default]
+    Nonpayable,
+
+    /// Skipped as read-only functions
+    View,
+}
+
+impl MissingMutability {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MissingMutability::Nonpayable => "nonpayable",
+            MissingMutability::View => "view",
+        }
+    }
+}
+
+/// Shape of `xvm_call`, which differs between Astar runtime versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum XvmVersion {
+    /// `pallet-xvm` v2 extension declared by the module, returning `Result<(), XvmError>`
+    V2,
+
+    /// `pallet-xvm` v3 extension declared by the module, taking an optional gas limit
+    /// and returning `Result<Vec<u8>, XvmError>`
+    V3,
+
+    /// Chain extension of the `xvm_environment` crate, returning `Result<Vec<u8>, _>`
+    #[default]
+    ChainExtension,
+}
+
+impl XvmVersion {
+    /// Whether the call returns output of the EVM call
+    pub fn returns_output(self) -> bool {
+        self != XvmVersion::V2
+    }
+
+    /// Whether the call takes a gas limit
+    pub fn takes_gas_limit(self) -> bool {
+        self == XvmVersion::V3
+    }
+}
+
+/// Call shape of the XVM version as seen by the templates
+#[derive(Debug, Clone, Copy, Serialize)]
+struct Xvm {
+    /// Environment the contract is declared with
+    environment: &'static str,
+
+    /// Whether the module declares the chain extension of the `pallet-xvm` version itself
+    v2: bool,
+    v3: bool,
+
+    /// Whether the gas limit of the calls is kept in storage and passed to the call
+    gas_limit: bool,
+
+    /// Template of the helper method performing the call
+    #[serde(skip)]
+    call: &'static str,
+}
+
+impl Xvm {
+    fn new(version: XvmVersion, gas_limit_setter: bool) -> Self {
+        let (environment, call) = match version {
+            XvmVersion::V2 => ("crate::XvmEnvironment", "xvm_call_v2"),
+            XvmVersion::V3 => ("crate::XvmEnvironment", "xvm_call_v3"),
+            XvmVersion::ChainExtension => ("xvm_environment::XvmDefaultEnvironment", "xvm_call"),
+        };
+
+        Xvm {
+            environment,
+            v2: version == XvmVersion::V2,
+            v3: version == XvmVersion::V3,
+            gas_limit: gas_limit_setter && version.takes_gas_limit(),
+            call,
+        }
+    }
+}
+
+/// How messages of overloaded functions are named
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Overloads {
+    /// Single message taking an enum with a variant per overload
+    #[default]
+    Enum,
+
+    /// Message per overload suffixed with its parameter types, i.e. `safe_transfer_from_address_address_uint_256`
+    Suffix,
+
+    /// Message per overload numbered in the ABI order, i.e. `safe_transfer_from` and `safe_transfer_from_2`
+    Index,
+}
+
+/// Suffix of the message named after the parameter types, i.e.
+/// `address_uint256_array` for `(address,uint256[])`
+fn type_suffix(function: &json::JsonValue) -> String {
+    function["inputs"]
+        .members()
+        .map(|input| {
+            canonical_type(input)
+                .replace("[]", "_array")
+                .replace('(', "tuple_")
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        })
+        .join("_")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .join("_")
+}
+
+/// Message names of the overloaded functions keyed by their signatures,
+/// empty when overloads share an enum message
+fn overload_names<'a>(
+    overloads: Overloads,
+    functions: impl Iterator<Item = &'a json::JsonValue>,
+) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    if overloads == Overloads::Enum {
+        return names;
+    }
+
+    let functions = functions.collect_vec();
+    let mut taken: HashSet<String> = functions
+        .iter()
+        .filter_map(|function| function["name"].as_str())
+        .map(|name| name.to_case(Case::Snake))
+        .collect();
+
+    let overloaded = functions
+        .iter()
+        .into_group_map_by(|function| function["name"].as_str().unwrap_or_default());
+
+    // ABI order keeps the numbering stable
+    for function in &functions {
+        let name = function["name"].as_str().unwrap_or_default();
+        let variants = &overloaded[name];
+        if variants.len() < 2 {
+            continue;
+        }
+
+        let position = variants
+            .iter()
+            .position(|variant| std::ptr::eq(**variant, *function))
+            .unwrap_or_default();
+
+        let message = match overloads {
+            Overloads::Suffix => match type_suffix(function) {
+                suffix if suffix.is_empty() => name.to_owned(),
+                suffix => format!("{name}_{suffix}"),
+            },
+            Overloads::Index if position == 0 => name.to_owned(),
+            Overloads::Index => {
+                // Skip numbers of functions already named that way
+                let mut index = position + 1;
+                while taken.contains(&format!("{name}{index}").to_case(Case::Snake)) {
+                    index += 1;
+                }
+                format!("{name}{index}")
+            }
+            Overloads::Enum => unreachable!("enum overloads are not renamed"),
+        };
+
+        taken.insert(message.to_case(Case::Snake));
+        names.insert(signature(function), message);
+    }
+
+    names
+}
+
+/// Functions taking the address of the caller instead of a message argument
+#[derive(Debug, Clone)]
+pub struct CallerForwarding {
+    /// Name of the `address` argument
+    pub argument: String,
+    pub filter: Filter,
+    pub mapping: CallerAddress,
+}
+
+/// How the EVM address is derived from the account of the caller
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct CallerAddress {
+    /// Last 20 bytes of Keccak256 hash of the account, otherwise its first 20 bytes
+    pub hashed: bool,
+}
+
+/// Rust types replacing the default mapping of the EVM types, see [`override_input`]
+#[derive(Debug, Clone, Default)]
+pub struct TypeOverrides {
+    /// Mapping of the EVM types, outputs only take the integer ones
+    pub types: Vec<(ParamType, String)>,
+
+    /// Types of the arguments keyed by function name and then by parameter name
+    pub params: BTreeMap<String, BTreeMap<String, String>>,
+
+    /// How EVM addresses are derived from the arguments mapped to `AccountId`
+    pub account_mapping: CallerAddress,
+}
+
+impl TypeOverrides {
+    /// Rust type of the argument, if overridden
+    fn input(&self, function: &str, param: &str, ty: &ParamType) -> Option<&str> {
+        self.params
+            .get(function)
+            .and_then(|params| params.get(param))
+            .or_else(|| self.mapped(ty))
+            .map(String::as_str)
+    }
+
+    fn mapped(&self, ty: &ParamType) -> Option<&String> {
+        self.types
+            .iter()
+            .find(|(other, _)| other == ty)
+            .map(|(_, rust_type)| rust_type)
+    }
+
+    /// Rust type of the output, if mapped to another integer type decoding it
+    fn output(&self, ty: &ParamType) -> Option<String> {
+        let signed = match ty {
+            ParamType::Int(_) => true,
+            ParamType::Uint(_) => false,
+            _ => return None,
+        };
+
+        self.mapped(ty)
+            .filter(|rust_type| int_size(rust_type, signed).is_some())
+            .cloned()
+    }
+
+    /// Whether any argument may take `AccountId`, which needs the account mapping
+    fn maps_accounts(&self) -> bool {
+        self.types
+            .iter()
+            .map(|(_, rust_type)| rust_type)
+            .chain(self.params.values().flat_map(|params| params.values()))
+            .any(|rust_type| rust_type == "AccountId")
+    }
+}
+
+/// Functions calling the contract stored in a separate field of the module
+#[derive(Debug, Clone)]
+pub struct TargetGroup {
+    pub name: String,
+    pub filter: Filter,
+
+    /// EVM ID of the contract if differs from the module one
+    pub evm_id: Option<String>,
+}
+
+impl Options {
+    /// Storage field holding address of the group contract
+    fn address_field(&self, group: Option<&TargetGroup>) -> String {
+        match group {
+            Some(group) => self.qualified(&format!("{}_evm_address", group.name)),
+            None => self.qualified("evm_address"),
+        }
+    }
+
+    /// Storage field and EVM ID of the contract the function calls
+    fn target(&self, name: &str) -> (String, String) {
+        let group = self
+            .target_groups
+            .iter()
+            .find(|group| group.filter.matches(name));
+
+        let evm_id = group
+            .and_then(|group| group.evm_id.clone())
+            .unwrap_or_else(|| "super::EVM_ID".to_owned());
+
+        (self.address_field(group), evm_id)
+    }
+
+    /// Whether the function input is filled with the caller address
+    fn is_from_caller(&self, function: &str, input: &str, ty: &ParamType) -> bool {
+        self.caller.as_ref().map_or(false, |caller| {
+            caller.argument == input && *ty == ParamType::Address && caller.filter.matches(function)
+        })
+    }
+
+    /// Feature of the first group the function belongs to
+    fn feature(&self, name: &str) -> Option<String> {
+        self.features
+            .iter()
+            .find(|(_, filter)| filter.matches(name))
+            .map(|(feature, _)| feature.clone())
+    }
+
+    fn call_defaults(&self, name: &str) -> CallDefaults {
+        self.call_defaults.get(name).copied().unwrap_or_default()
+    }
+
+    /// Doc comment lines of the function from the docs file
+    fn docs(&self, name: &str) -> Vec<String> {
+        self.docs
+            .as_ref()
+            .and_then(|docs| docs.get(name))
+            .map(|entry| docs::comment_lines(&entry.doc))
+            .unwrap_or_default()
+    }
+
+    /// Example lines of the function from the docs file
+    fn example(&self, name: &str) -> Vec<String> {
+        self.docs
+            .as_ref()
+            .and_then(|docs| docs.get(name))
+            .map(|entry| docs::comment_lines(&entry.example))
+            .unwrap_or_default()
+    }
+
+    /// Name prefixed with the namespace, if any
+    fn qualified(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}_{name}"),
+            None => name.to_owned(),
+        }
+    }
+}
+
+/// State mutability of the function. ABIs of old compilers have `constant`
+/// and `payable` flags instead, hand-written ABIs may have neither.
+pub fn state_mutability(function: &json::JsonValue) -> Option<&str> {
+    if let Some(mutability) = function["stateMutability"].as_str() {
+        return Some(mutability);
+    }
+
+    match (
+        function["constant"].as_bool(),
+        function["payable"].as_bool(),
+    ) {
+        (Some(true), _) => Some("view"),
+        (_, Some(true)) => Some("payable"),
+        (Some(false), _) | (_, Some(false)) => Some("nonpayable"),
+        (None, None) => None,
+    }
+}
+
+/// ABI functions that could be wrapped along with their indices
+pub fn functions<'j>(
+    json: &'j json::JsonValue,
+    filter: &'j Filter,
+    missing: MissingMutability,
+    include_views: bool,
+) -> impl Iterator<Item = (usize, &'j json::JsonValue)> + 'j {
+    json.members()
+        .enumerate()
+        .filter(|(_, item)| item["type"] == "function")
+        .filter(move |(_, item)| {
+            item["name"]
+                .as_str()
+                .map_or(true, |name| filter.matches(name))
+        })
+        .filter(move |(_, item)| skip_reason(item, missing, include_views).is_none())
+}
+
+/// Why the function cannot be wrapped, if it cannot
+fn skip_reason(
+    function: &json::JsonValue,
+    missing: MissingMutability,
+    include_views: bool,
+) -> Option<SkipReason> {
+    match state_mutability(function).unwrap_or(missing.as_str()) {
+        "nonpayable" | "payable" => {}
+        "view" | "pure" if include_views => {}
+        "view" | "pure" => return Some(SkipReason::ReadOnly),
+        _ => return Some(SkipReason::UnknownMutability),
+    }
+
+    if function["outputs"].len() > MAX_OUTPUTS {
+        Some(SkipReason::TooManyOutputs)
+    } else if takes_storage_reference(function) {
+        Some(SkipReason::StorageReference)
+    } else {
+        None
+    }
+}
+
+/// Explains which filters removed every function of the ABI
+fn empty_reason(json: &json::JsonValue, options: &Options) -> String {
+    let all: Vec<_> = json
+        .members()
+        .filter(|item| item["type"] == "function")
+        .collect();
+
+    if all.is_empty() {
+        return "the ABI has no functions".to_owned();
+    }
+
+    let (matched, unmatched): (Vec<_>, Vec<_>) = all.iter().partition(|function| {
+        function["name"]
+            .as_str()
+            .map_or(true, |name| options.filter.matches(name))
+    });
+
+    let skipped: Vec<_> = matched
+        .iter()
+        .filter_map(|function| {
+            skip_reason(function, options.missing_mutability, options.include_views)
+        })
+        .collect();
+
+    let mut reasons = Vec::new();
+    if !unmatched.is_empty() {
+        reasons.push(format!(
+            "{} excluded by --only or --exclude",
+            unmatched.len()
+        ));
+    }
+
+    for reason in SkipReason::ALL {
+        let count = skipped.iter().filter(|&&skipped| skipped == reason).count();
+        let hint = match reason {
+            SkipReason::ReadOnly => ", see --include-views",
+            _ => "",
+        };
+
+        if count > 0 {
+            reasons.push(format!("{count} {}{hint}", reason.description()));
+        }
+    }
+
+    format!("of {} functions {}", all.len(), reasons.join(", "))
+}
+
+/// Gas estimate Vyper and some other tools put into ABI entries
+fn gas_hint(function: &json::JsonValue) -> Option<u64> {
+    function["gas"]
+        .as_u64()
+        .or_else(|| function["gas"].as_str()?.parse().ok())
+}
+
+/// Library functions may take storage pointers, i.e. `Set.Data storage`,
+/// which are not a part of the ABI and cannot be passed in an external call
+fn takes_storage_reference(function: &json::JsonValue) -> bool {
+    function["inputs"].members().any(|input| {
+        input["type"]
+            .as_str()
+            .map_or(false, |ty| ty.ends_with(" storage"))
+    })
+}
+
+/// Only Solidity libraries have functions taking storage references
+fn is_library(json: &json::JsonValue) -> bool {
+    json.members()
+        .filter(|item| item["type"] == "function")
+        .any(takes_storage_reference)
+}
+
+/// User templates taking precedence over the built-in ones. Texts are
+/// loaded once and live until the process exits, like the built-in ones.
+#[derive(Debug, Default, Clone)]
+pub struct CustomTemplates {
+    /// Template of the whole module, rendered from the model instead of the sections
+    module: Option<&'static str>,
+
+    /// Sections and partials keyed by the file stem, i.e. `message` for `message.txt`
+    sections: Vec<(&'static str, &'static str)>,
+}
+
+impl CustomTemplates {
+    pub fn load(module: Option<&Path>, dir: Option<&Path>) -> Result<Self, Error> {
+        Ok(CustomTemplates {
+            module: module.map(read_template).transpose()?,
+            sections: match dir {
+                Some(dir) => read_sections(dir)?,
+                None => Vec::new(),
+            },
+        })
+    }
+
+    /// Adds templates of a shared pack, which give way to the ones loaded before.
+    /// The `module` template of the pack renders the whole module.
+    pub fn extend(&mut self, dir: &Path) -> Result<(), Error> {
+        for (name, text) in read_sections(dir)? {
+            if name == "module" {
+                self.module.get_or_insert(text);
+            } else if self.sections.iter().all(|&(other, _)| other != name) {
+                self.sections.push((name, text));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Summary of the texts independent of the order the files were read in,
+    /// so outputs of batch mode are regenerated when any template changes
+    pub fn fingerprint(&self) -> String {
+        let mut sections = self.sections.clone();
+        sections.sort_unstable();
+        format!("{:?} {sections:?}", self.module)
+    }
+}
+
+fn read_template(path: &Path) -> Result<&'static str, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::ReadInput {
+        path: path.to_owned(),
+        inner: e,
+    })?;
+
+    Ok(Box::leak(text.into_boxed_str()))
+}
+
+/// Reads `.txt` templates of the directory, keyed by the file stem
+fn read_sections(dir: &Path) -> Result<Vec<(&'static str, &'static str)>, Error> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::ReadInput {
+        path: dir.to_owned(),
+        inner: e,
+    })?;
+
+    let mut sections = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(true, |extension| extension != "txt")
+        {
+            continue;
+        }
+
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            sections.push((name, read_template(&path)?));
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Module generator holding compiled templates, so they
+/// could be reused to render any number of modules.
+pub struct Generator<'template> {
+    template: TinyTemplate<'template>,
+
+    /// Whether the whole module is rendered by the user template
+    renders_module: bool,
+
+    /// Case conversions are costly, so every identifier
+    /// is converted once and then shared across modules.
+    identifiers: RefCell<HashMap<String, Rc<Identifier>>>,
+
+    /// Whether the target calls EVM contracts through the XVM chain extension
+    calls_xvm: bool,
+
+    /// Whether messages are runtime functions building calls rather than ink! messages
+    builds_calls: bool,
+}
+
+impl Generator<'static> {
+    pub fn new(target: &Target, custom: &CustomTemplates) -> Result<Self, Error> {
+        let mut template = TinyTemplate::new();
+
+        let target_templates = match target {
+            Target::AstarXvm => XVM_TEMPLATES,
+            Target::MoonbeamXcm => XCM_TEMPLATES,
+            Target::FrontierRuntime => FRONTIER_TEMPLATES,
+        };
+
+        let is_custom = |name: &str| custom.sections.iter().any(|(other, _)| name == *other);
+        let target_templates = target_templates
+            .iter()
+            .filter(|&&(name, _)| !is_custom(name));
+
+        let common_templates = TEMPLATES.iter().filter(|&&(name, _)| {
+            !is_custom(name) && target_templates.clone().all(|&(other, _)| name != other)
+        });
+
+        template.set_default_formatter(&format_unescaped);
+        for &(name, text) in custom
+            .sections
+            .iter()
+            .chain(target_templates.clone())
+            .chain(common_templates)
+        {
+            template.add_template(name, text)?;
+        }
+
+        if let Some(text) = custom.module {
+            template.add_template("module", text)?;
+        }
+
+        template.add_formatter("snake", |value, buffer| match value {
+            serde_json::Value::String(s) => {
+                buffer.push_str(&s.to_case(Case::Snake));
+                Ok(())
+            }
+            _ => Err(tinytemplate::error::Error::GenericError {
+                msg: "string value expected".to_owned(),
+            }),
+        });
+
+        template.add_formatter("upper_snake", |value, buffer| match value {
+            serde_json::Value::String(s) => {
+                buffer.push_str(&s.to_case(Case::UpperSnake));
+                Ok(())
+            }
+            _ => Err(tinytemplate::error::Error::GenericError {
+                msg: "string value expected".to_owned(),
+            }),
+        });
+
+        template.add_formatter("upper_camel", |value, buffer| match value {
+            serde_json::Value::String(s) => {
+                buffer.push_str(&s.to_case(Case::UpperCamel));
+                Ok(())
+            }
+            _ => Err(tinytemplate::error::Error::GenericError {
+                msg: "string value expected".to_owned(),
+            }),
+        });
+
+        template.add_formatter("capitalize", |value, buffer| match value {
+            serde_json::Value::String(s) => {
+                let (head, tail) = s.split_at(1);
+
+                buffer.push_str(&head.to_uppercase());
+                buffer.push_str(tail);
+
+                Ok(())
+            }
+            _ => Err(tinytemplate::error::Error::GenericError {
+                msg: "string value expected".to_owned(),
+            }),
+        });
+
+        Ok(Generator {
+            template,
+            renders_module: custom.module.is_some(),
+            identifiers: RefCell::default(),
+            calls_xvm: matches!(target, Target::AstarXvm),
+            builds_calls: matches!(target, Target::FrontierRuntime),
+        })
+    }
+}
+
+impl<'template> Generator<'template> {
+    fn identifier(&self, name: &str) -> Rc<Identifier> {
+        self.identifiers
+            .borrow_mut()
+            .entry(name.to_owned())
+            .or_insert_with(|| Rc::new(Identifier::new(name)))
+            .clone()
+    }
+
+    /// Whether the target returns data of the EVM call, so messages could decode it
+    fn decodes_output(&self, options: &Options) -> bool {
+        self.calls_xvm && options.xvm_version.returns_output()
+    }
+
+    /// Single return value of the function, if the target lets messages decode it
+    fn output(
+        &self,
+        name: &str,
+        function: &json::JsonValue,
+        options: &Options,
+        structs: &mut Structs,
+    ) -> Result<Option<Output>, ParamError> {
+        let outputs = &function["outputs"];
+        if !self.decodes_output(options) || outputs.is_empty() {
+            return Ok(None);
+        }
+
+        let param_types = outputs
+            .members()
+            .enumerate()
+            .map(|(index, output)| {
+                let ty = canonical_type(output);
+                ethabi::param_type::Reader::read(&ty).map_err(|_| ParamError::UnsupportedOutput {
+                    function: name.to_owned(),
+                    index,
+                    ty,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let overrides = &options.type_overrides;
+        if let [param_type] = param_types.as_slice() {
+            let is_bool = *param_type == ParamType::Bool;
+            let test_token = if is_bool {
+                Token::Bool(true)
+            } else {
+                zero_token(param_type)
+            };
+
+            return Ok(Some(Output {
+                rust_type: overrides
+                    .output(param_type)
+                    .unwrap_or_else(|| structs.rust_type(&outputs[0], param_type)),
+                decode: format!("decode_output(&output, {})", param_type_expr(param_type)),
+                is_bool,
+                test_output: ethabi::encode(&[test_token]).encode_hex(),
+            }));
+        }
+
+        let tokens = param_types.iter().map(zero_token).collect_vec();
+        Ok(Some(Output {
+            rust_type: format!(
+                "({})",
+                outputs
+                    .members()
+                    .zip(&param_types)
+                    .map(|(output, ty)| overrides
+                        .output(ty)
+                        .unwrap_or_else(|| structs.rust_type(output, ty)))
+                    .join(", ")
+            ),
+            decode: format!(
+                "decode_outputs(&output, &[{}])",
+                param_types.iter().map(param_type_expr).join(", ")
+            ),
+            is_bool: false,
+            test_output: ethabi::encode(&tokens).encode_hex(),
+        }))
+    }
+
+    /// ink! events mirroring events of the ABI. Runtime modules have no events.
+    fn events(
+        &self,
+        json: &json::JsonValue,
+        options: &Options,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Vec<Event>, Error> {
+        if self.builds_calls {
+            return Ok(Vec::new());
+        }
+
+        let mut seen = HashMap::new();
+        let mut events = Vec::new();
+
+        for event in json.members().filter(|item| item["type"] == "event") {
+            let name = event["name"]
+                .as_str()
+                .ok_or_else(|| Error::Metadata("event without a name".to_owned()))?;
+
+            // Overloaded events get numbered, i.e. `Transfer` and `Transfer2`
+            let count = seen
+                .entry(name)
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+            let name = match *count {
+                1 => name.to_owned(),
+                count => {
+                    let numbered = format!("{name}{count}");
+                    warnings.push(Warning::Renamed {
+                        module: options.module_name.clone(),
+                        from: signature(event),
+                        to: numbered.clone(),
+                    });
+                    numbered
+                }
+            };
+
+            let mut topics = 0;
+            let fields = event["inputs"]
+                .members()
+                .enumerate()
+                .map(|(index, input)| {
+                    let param_type = ethabi::param_type::Reader::read(&canonical_type(input))?;
+                    let indexed = input["indexed"].as_bool().unwrap_or_default();
+                    let topic = indexed && topics < MAX_EVENT_TOPICS;
+                    topics += topic as usize;
+
+                    let name = field_name(input, index);
+
+                    // Indexed values of reference types are only logged as their hash
+                    let rust_type = match param_type {
+                        ParamType::Bytes
+                        | ParamType::String
+                        | ParamType::Array(_)
+                        | ParamType::FixedArray(..)
+                        | ParamType::Tuple(_)
+                            if indexed =>
+                        {
+                            warnings.push(Warning::LossyType {
+                                module: options.module_name.clone(),
+                                item: signature(event),
+                                parameter: name.clone(),
+                                evm_type: canonical_type(input),
+                                rust_type: "FixedBytes<32>".to_owned(),
+                            });
+                            "FixedBytes<32>".to_owned()
+                        }
+                        _ => convert_type(&param_type),
+                    };
+
+                    Ok(EventField {
+                        name,
+                        rust_type,
+                        topic,
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+
+            events.push(Event {
+                ident: self.identifier(&options.qualified(&name)),
+                signature: signature(event),
+                fields,
+            });
+        }
+
+        Ok(events)
+    }
+
+    /// Variants of the module error enum for custom errors of the ABI
+    fn errors(
+        &self,
+        json: &json::JsonValue,
+        options: &Options,
+        structs: &mut Structs,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Vec<CustomError>, Error> {
+        let mut seen = HashMap::new();
+        let mut errors = Vec::new();
+
+        for error in json.members().filter(|item| item["type"] == "error") {
+            let name = error["name"]
+                .as_str()
+                .ok_or_else(|| Error::Metadata("error without a name".to_owned()))?;
+
+            // Overloaded errors get numbered the same way as events
+            let count = seen
+                .entry(name)
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+            let name = match *count {
+                1 => name.to_owned(),
+                count => {
+                    let numbered = format!("{name}{count}");
+                    warnings.push(Warning::Renamed {
+                        module: options.module_name.clone(),
+                        from: signature(error),
+                        to: numbered.clone(),
+                    });
+                    numbered
+                }
+            };
+
+            let fields = error["inputs"]
+                .members()
+                .enumerate()
+                .map(|(index, input)| {
+                    let param_type = ethabi::param_type::Reader::read(&canonical_type(input))?;
+                    Ok(ErrorField {
+                        name: field_name(input, index),
+                        rust_type: structs.rust_type(input, &param_type),
+                        param_type: param_type_expr(&param_type),
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+
+            errors.push(CustomError {
+                ident: self.identifier(&name),
+                selector_bytes: selector_bytes(&self::selector(&signature(error))),
+                signature: signature(error),
+                fields,
+            });
+        }
+
+        Ok(errors)
+    }
+
+    /// Invocation of the message with zero values, so the example always matches
+    /// the signature. Nothing is generated if some argument has no zero value.
+    fn call_example(&self, options: &Options, message: &str, arguments: &[Input]) -> Vec<String> {
+        match arguments
+            .iter()
+            .map(|input| input.zero_value.clone())
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(values) => self.example_lines(options, message, &values.join(", ")),
+            None => Vec::new(),
+        }
+    }
+
+    /// Invocation of the overloaded message with the first variant that can be constructed
+    fn overloaded_call_example(
+        &self,
+        options: &Options,
+        function: &OverloadedFunction,
+    ) -> Vec<String> {
+        let values = function.variants.iter().find_map(|variant| {
+            variant
+                .arguments
+                .iter()
+                .map(|input| input.zero_value.as_ref().map(|value| format!("{value},")))
+                .collect::<Option<Vec<_>>>()
+        });
+
+        match values {
+            Some(values) => {
+                let args = format!(
+                    "{}Args::from(({}))",
+                    function.ident.upper_camel,
+                    values.join(" ")
+                );
+                self.example_lines(options, &function.ident.snake, &args)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Lines instantiating the module and calling the message, or building the call
+    fn example_lines(&self, options: &Options, message: &str, args: &str) -> Vec<String> {
+        let module = &options.module_name;
+        if self.builds_calls {
+            let args = [String::from("&params"), args.to_owned()]
+                .into_iter()
+                .filter(|arg| !arg.is_empty())
+                .join(", ");
+
+            return vec![format!(
+                " let call = {module}::{message}::<Runtime>({args});"
+            )];
+        }
+
+        let (head, tail) = module.split_at(1);
+        let addresses = vec!["H160::from([0x11; 20])"; options.target_groups.len() + 1];
+
+        vec![
+            format!(
+                " let mut {module} = {}{tail}::new({});",
+                head.to_uppercase(),
+                addresses.join(", ")
+            ),
+            format!(" let result = {module}.{message}({args});"),
+        ]
+    }
+
+    /// Builds model of the module from the ABI
+    pub fn model(&self, json: &json::JsonValue, options: &Options) -> Result<Model, Error> {
+        ident::validate_module_name(&options.module_name)?;
+        if let Some(namespace) = &options.namespace {
+            ident::validate_module_name(namespace)?;
+        }
+
+        let mut warnings = Vec::new();
+        if is_library(json) {
+            // XVM performs regular calls, but library functions
+            // modifying state revert unless called with DELEGATECALL
+            warnings.push(Warning::Library {
+                module: options.module_name.clone(),
+            });
+        }
+
+        let missing = json
+            .members()
+            .filter(|item| item["type"] == "function" && state_mutability(item).is_none())
+            .count();
+
+        if missing > 0 {
+            warnings.push(Warning::MissingMutability {
+                module: options.module_name.clone(),
+                count: missing,
+                assumed: options.missing_mutability.as_str(),
+            });
+        }
+
+        for function in json.members().filter(|item| {
+            item["type"] == "function"
+                && item["name"]
+                    .as_str()
+                    .map_or(true, |name| options.filter.matches(name))
+        }) {
+            if let Some(reason) =
+                skip_reason(function, options.missing_mutability, options.include_views)
+            {
+                warnings.push(Warning::SkippedFunction {
+                    module: options.module_name.clone(),
+                    signature: signature(function),
+                    reason,
+                });
+            }
+        }
+
+        let mut is_overloaded = HashMap::new();
+        for (index, function) in functions(
+            json,
+            &options.filter,
+            options.missing_mutability,
+            options.include_views,
+        ) {
+            let function_name = function["name"].as_str().ok_or_else(|| {
+                Error::Metadata(format!(
+                    "'name' for ABI item {index} not exists or is not a string"
+                ))
+            })?;
+
+            is_overloaded
+                .entry(function_name)
+                .and_modify(|v| *v = true)
+                .or_insert(false);
+        }
+
+        let overload_names = overload_names(
+            options.overloads,
+            functions(
+                json,
+                &options.filter,
+                options.missing_mutability,
+                options.include_views,
+            )
+            .map(|(_, function)| function),
+        );
+
+        // Selectors of trait messages ink! derives from the trait path
+        let trait_name = (options.trait_definition && !self.builds_calls)
+            .then(|| format!("{}Like", options.module_name.to_case(Case::UpperCamel)));
+
+        let mut overloaded_functions = Vec::<OverloadedFunction>::new();
+        let mut functions = Vec::new();
+        let mut problems = Vec::new();
+        let mut structs = Structs {
+            decodes: self.decodes_output(options),
+            ..Default::default()
+        };
+
+        for (index, function) in functions(
+            json,
+            &options.filter,
+            options.missing_mutability,
+            options.include_views,
+        ) {
+            let function_name = function["name"].as_str().ok_or_else(|| {
+                Error::Metadata(format!(
+                    "'name' for ABI item {index} not exists or is not a string"
+                ))
+            })?;
+
+            let is_view = matches!(
+                state_mutability(function).unwrap_or(options.missing_mutability.as_str()),
+                "view" | "pure"
+            );
+            let inputs = function["inputs"]
+                .members()
+                .enumerate()
+                .map(|(index, input)| {
+                    let (name, raw_type, param_type) = parse_param(function_name, index, input)?;
+                    let converted = structs.rust_type(input, &param_type);
+                    let (token_type, detokenize) = token_type(&param_type);
+                    let from_caller = options.is_from_caller(function_name, &name, &param_type);
+
+                    let input = Input {
+                        tokenize: tokenize_param(input, &param_type, &name),
+                        example: structs.example(input, &param_type),
+                        // Tests construct tuples, not structs
+                        zero_value: zero_value(&param_type)
+                            .filter(|_| struct_type(input).is_none()),
+                        name,
+                        evm_type: raw_type,
+                        rust_type: converted,
+                        param_type: param_type_expr(&param_type),
+                        token_type: token_type.to_owned(),
+                        detokenize: detokenize.to_owned(),
+                        zero_token: zero_token(&param_type),
+                        from_caller,
+                        min_size: min_encoded_size(&param_type),
+                    };
+
+                    // Runtime calls and the caller address keep the EVM types
+                    let input = match options.native_types {
+                        Some(mapping) if !self.builds_calls && !from_caller => {
+                            native_input(input, &param_type, mapping)
+                        }
+                        _ => input,
+                    };
+
+                    let overrides = &options.type_overrides;
+                    match overrides.input(function_name, &input.name, &param_type) {
+                        Some(rust_type) if !self.builds_calls && !from_caller => {
+                            let (param, ty) = (input.name.clone(), input.evm_type.clone());
+                            override_input(input, &param_type, rust_type, overrides.account_mapping)
+                                .ok_or_else(|| ParamError::UnsupportedOverride {
+                                    function: function_name.to_owned(),
+                                    param,
+                                    ty,
+                                    rust_type: rust_type.to_owned(),
+                                })
+                        }
+                        _ => Ok(input),
+                    }
+                })
+                .filter_map(|input| input.map_err(|problem| problems.push(problem)).ok())
+                .collect_vec();
+
+            let returns = match self.output(function_name, function, options, &mut structs) {
+                Ok(returns) => returns,
+                Err(problem) => {
+                    problems.push(problem);
+                    continue;
+                }
+            };
+
+            // Problems of all functions are reported at once
+            if inputs.len() != function["inputs"].len() {
+                continue;
+            }
+
+            let selector = format!(
+                "{function_name}({args})",
+                args = inputs.iter().map(|input| input.evm_type.as_str()).join(","),
+            );
+
+            if !self.builds_calls {
+                let size = arguments(&inputs)
+                    .iter()
+                    .map(|input| input.min_size)
+                    .sum::<usize>();
+
+                if size > MAX_ENCODED_ARGUMENTS {
+                    warnings.push(Warning::LargeArguments {
+                        module: options.module_name.clone(),
+                        signature: selector.clone(),
+                        size,
+                        limit: MAX_ENCODED_ARGUMENTS,
+                    });
+                }
+            }
+
+            let selector_hash = self::selector(&selector);
+            let (address_field, evm_id) = options.target(function_name);
+            // Configured gas comes first, then measured gas and the estimate of the compiler
+            let defaults = options.call_defaults(function_name);
+            let gas = defaults
+                .gas
+                .or_else(|| {
+                    options
+                        .gas_report
+                        .as_ref()
+                        .and_then(|report| report.get(function_name, &selector))
+                })
+                .or_else(|| gas_hint(function));
+            let value = defaults.value.map(|value| value.to_string());
+
+            let message_name = overload_names.get(&signature(function));
+            if is_overloaded[function_name] && message_name.is_none() {
+                let function = {
+                    if let Some(function) = overloaded_functions
+                        .iter_mut()
+                        .find(|f| f.name == function_name)
+                    {
+                        function
+                    } else {
+                        let ident = self.identifier(&options.qualified(function_name));
+                        let message_selector = match &trait_name {
+                            Some(trait_name) => {
+                                self::message_selector(&format!("{trait_name}::{}", ident.snake))
+                            }
+                            None => self::message_selector(&ident.snake),
+                        };
+                        overloaded_functions.push(OverloadedFunction {
+                            name: function_name.to_owned(),
+                            ident,
+                            variants: Vec::new(),
+                            message_selector: message_selector.encode_hex(),
+                            message_selector_bytes: selector_bytes(&message_selector),
+                            gas: None,
+                            value,
+                            returns: None,
+                            is_view: true,
+                            docs: options.docs(function_name),
+                            example: options.example(function_name),
+                            feature: options.feature(function_name),
+                            address_field,
+                            evm_id,
+                        });
+
+                        overloaded_functions
+                            .last_mut()
+                            .expect("we've just pushed an item; cannot fail")
+                    }
+                };
+
+                if self.decodes_output(options)
+                    && returns.as_ref().map_or(false, |returns| !returns.is_bool)
+                {
+                    warnings.push(Warning::DiscardedOutput {
+                        module: options.module_name.clone(),
+                        signature: selector.clone(),
+                    });
+                }
+
+                function.gas = function.gas.max(gas);
+                function.is_view &= is_view;
+                function.variants.push(Variant {
+                    test_input: test_input(&selector_hash, &inputs),
+                    arguments: arguments(&inputs),
+                    inputs,
+                    selector,
+                    selector_hash: selector_hash.encode_hex(),
+                    selector_bytes: selector_bytes(&selector_hash),
+                    gas,
+                })
+            } else {
+                // Without a namespace message is selected the same way as the EVM function
+                let message_selector = match options.namespace {
+                    Some(_) => self::selector(&options.qualified(&selector)),
+                    None => selector_hash,
+                };
+
+                let ident = self.identifier(
+                    &options.qualified(message_name.map_or(function_name, String::as_str)),
+                );
+                let arguments = arguments(&inputs);
+
+                // Builders of runtime calls are plain functions without the limit
+                let (arguments, packed) = if !self.builds_calls && arguments.len() > MAX_ARGUMENTS {
+                    warnings.push(Warning::PackedArguments {
+                        module: options.module_name.clone(),
+                        signature: selector.clone(),
+                        count: arguments.len(),
+                    });
+
+                    (vec![packed_argument(&ident, &arguments)], arguments)
+                } else {
+                    (arguments, Vec::new())
+                };
+
+                let example = match options.example(function_name) {
+                    example if example.is_empty() => {
+                        let arguments = if self.builds_calls {
+                            &inputs
+                        } else {
+                            &arguments
+                        };
+                        self.call_example(options, &ident.snake, arguments)
+                    }
+                    example => example,
+                };
+
+                functions.push(Function {
+                    name: function_name.to_owned(),
+                    ident,
+                    test_input: test_input(&selector_hash, &inputs),
+                    arguments,
+                    packed,
+                    inputs,
+                    output: self.message_output(options, returns.as_ref()),
+                    returns,
+                    is_view,
+                    selector,
+                    selector_hash: selector_hash.encode_hex(),
+                    selector_bytes: selector_bytes(&selector_hash),
+                    gas,
+                    value,
+                    docs: options.docs(function_name),
+                    example,
+                    feature: options.feature(function_name),
+                    address_field,
+                    evm_id,
+                    message_selector: message_selector.encode_hex(),
+                    message_selector_bytes: selector_bytes(&message_selector),
+                });
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(Error::Params(problems));
+        }
+
+        for function in &mut overloaded_functions {
+            if function.example.is_empty() {
+                function.example = self.overloaded_call_example(options, function);
+            }
+        }
+
+        let stub = functions.is_empty() && overloaded_functions.is_empty();
+        if stub && !options.allow_empty {
+            return Err(Error::EmptyModule {
+                module: options.module_name.clone(),
+                reason: empty_reason(json, options),
+            });
+        }
+
+        let address_fields: Vec<_> = std::iter::once(None)
+            .chain(options.target_groups.iter().map(Some))
+            .map(|group| options.address_field(group))
+            .collect();
+
+        let psp22 = match options.psp22 {
+            Some(_) => {
+                self.adapted_functions(json, "psp22", "ERC20", ERC20_FUNCTIONS)?;
+                Some(Psp22 {
+                    address_field: address_fields[0].clone(),
+                })
+            }
+            None => None,
+        };
+
+        let psp34 = match options.psp34 {
+            Some(_) => {
+                let signatures =
+                    self.adapted_functions(json, "psp34", "ERC721", ERC721_FUNCTIONS)?;
+                Some(Psp34 {
+                    address_field: address_fields[0].clone(),
+                    enumerable: signatures.contains("totalSupply()"),
+                })
+            }
+            None => None,
+        };
+
+        let erc721_received = functions
+            .iter()
+            .map(|function| &function.name)
+            .chain(overloaded_functions.iter().map(|function| &function.name))
+            .any(|name| name == "safeTransferFrom")
+            .then(|| self::selector(ON_ERC721_RECEIVED).encode_hex());
+
+        let errors = if options.typed_errors && self.decodes_output(options) {
+            self.errors(json, options, &mut structs, &mut warnings)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Model {
+            module: Module {
+                name: options.module_name.clone(),
+                evm_id: options.evm_id.clone(),
+                para_id: options.para_id,
+                namespace: options.namespace.clone(),
+                address_fields,
+                imports: options.imports.clone(),
+                attributes: options.attributes.clone(),
+                storage: options.storage.clone(),
+                caller: options.caller.as_ref().map(|caller| caller.mapping),
+                erc721_received,
+                abi_hash: stamp::abi_hash(serde_json::from_str(&json.dump())?)?,
+                selector_table: options.selector_table,
+                stub,
+                signature_helpers: options.signature_helpers,
+                account_mapping: options
+                    .native_types
+                    .or_else(|| {
+                        let overrides = &options.type_overrides;
+                        overrides
+                            .maps_accounts()
+                            .then_some(overrides.account_mapping)
+                    })
+                    .filter(|_| !self.builds_calls)
+                    .or(options.psp22)
+                    .or(options.psp34),
+                // Stubs have no interface to speak of
+                trait_name: trait_name.filter(|_| !stub),
+                typed_errors: options.typed_errors && self.decodes_output(options),
+                psp22,
+                psp34,
+                xvm: Xvm::new(options.xvm_version, options.gas_limit_setter),
+                ink: options.ink.clone(),
+                shared_types: options.shared_types.clone(),
+            },
+            events: self.events(json, options, &mut warnings)?,
+            functions,
+            overloaded_functions,
+            structs: structs.defined,
+            errors,
+            warnings,
+        })
+    }
+
+    /// Signatures of the functions of the ABI, which has to implement the standard the
+    /// adapter forwards to. Views of the contract are called regardless of the filters,
+    /// so only targets decoding results support adapters.
+    fn adapted_functions(
+        &self,
+        json: &json::JsonValue,
+        adapter: &str,
+        standard: &str,
+        required: &[&str],
+    ) -> Result<HashSet<String>, Error> {
+        let error = |reason: String| Error::Adapter {
+            adapter: adapter.to_owned(),
+            reason,
+        };
+
+        if !self.calls_xvm {
+            return Err(error(
+                "only the astar-xvm target decodes results of the calls".to_owned(),
+            ));
+        }
+
+        let signatures = json
+            .members()
+            .filter(|item| item["type"] == "function")
+            .map(signature)
+            .collect::<HashSet<_>>();
+
+        match required
+            .iter()
+            .find(|function| !signatures.contains(**function))
+        {
+            Some(missing) => Err(error(format!(
+                "the ABI has no `{missing}`, so it is not {standard}"
+            ))),
+            None => Ok(signatures),
+        }
+    }
+
+    /// Return type of the message calling the function with the given results
+    fn message_output(&self, options: &Options, returns: Option<&Output>) -> String {
+        let returns = returns.filter(|returns| !returns.is_bool);
+        if options.typed_errors && self.decodes_output(options) {
+            format!(
+                "Result<{}, XvmCallError>",
+                returns.map_or("()", |returns| &returns.rust_type)
+            )
+        } else {
+            returns.map_or_else(
+                || "bool".to_owned(),
+                |returns| format!("Option<{}>", returns.rust_type),
+            )
+        }
+    }
+
+    /// Renders module to the writer section by section
+    pub fn render_to(&self, model: &Model, writer: &mut dyn Write) -> Result<(), Error> {
+        if self.renders_module {
+            return self.render_section("module", model, writer);
+        }
+
+        let module = &model.module;
+
+        self.render_section("prologue", module, writer)?;
+        if !module.selector_table {
+            self.render_chunks("constant", module, &model.functions, writer)?;
+        } else if !model.functions.is_empty() {
+            let table = SelectorTable {
+                module,
+                functions: &model.functions,
+            };
+            self.render_section("selector_table", &table, writer)?;
+        }
+        self.render_section("storage", module, writer)?;
+        if module.account_mapping.is_some() {
+            self.render_section("account_address", module, writer)?;
+        }
+        if module.signature_helpers {
+            self.render_section("crypto", module, writer)?;
+            self.render_section("eip191", module, writer)?;
+        }
+        if module.typed_errors {
+            self.render_section("call_error", module, writer)?;
+        }
+        if !model.errors.is_empty() {
+            self.render_section("custom_error", model, writer)?;
+        }
+        self.render_events(module, &model.events, writer)?;
+        self.render_chunks("struct", module, &model.structs, writer)?;
+        self.render_chunks(
+            "overloaded_args",
+            module,
+            &model.overloaded_functions,
+            writer,
+        )?;
+        self.render_chunks("packed_args", module, &model.packed_functions(), writer)?;
+        if module.trait_name.is_some() {
+            self.render_section("trait_definition", model, writer)?;
+        }
+        if module.psp22.is_some() {
+            self.render_section("psp22", module, writer)?;
+        }
+        if module.psp34.is_some() {
+            self.render_section("psp34", module, writer)?;
+        }
+        if self.calls_xvm {
+            self.render_section(module.xvm.call, module, writer)?;
+        }
+        // Runtime modules have plain functions instead of the contract impl
+        if !self.builds_calls {
+            self.render_section("impl", module, writer)?;
+        }
+        self.render_chunks(
+            "overloaded_message",
+            module,
+            &model.overloaded_functions,
+            writer,
+        )?;
+        self.render_chunks("message", module, &model.functions, writer)?;
+        if module.stub {
+            self.render_section("raw_call", module, writer)?;
+        }
+        self.render_section("epilogue", module, writer)?;
+
+        Ok(())
+    }
+
+    /// Renders off-chain unit tests of the module messages, calling
+    /// them against mocked XVM chain extension
+    pub fn render_tests_to(&self, model: &Model, writer: &mut dyn Write) -> Result<(), Error> {
+        let module = &model.module;
+
+        self.render_section("tests_prologue", module, writer)?;
+        self.render_chunks(
+            "overloaded_test",
+            module,
+            &model.overloaded_functions,
+            writer,
+        )?;
+        self.render_chunks("test", module, &model.functions, writer)?;
+        self.render_section("tests_epilogue", module, writer)?;
+
+        Ok(())
+    }
+
+    /// Renders `#[ink_e2e::test]` skeletons, instantiating the module
+    /// on a local node and calling every message with zero arguments
+    pub fn render_e2e_tests_to(&self, model: &Model, writer: &mut dyn Write) -> Result<(), Error> {
+        let module = &model.module;
+
+        self.render_section("e2e_prologue", module, writer)?;
+        self.render_chunks("e2e_test", module, &model.functions, writer)?;
+        self.render_section("tests_epilogue", module, writer)?;
+
+        Ok(())
+    }
+
+    /// Inserts functions missing from the existing module in place, leaving the
+    /// rest of it untouched. Returns the updated module and the added signatures.
+    pub fn append_to(&self, model: &Model, existing: &str) -> Result<(String, Vec<String>), Error> {
+        let module = &model.module;
+        let is_present = |selector: &str| existing.contains(&format!("`{selector}`"));
+        let is_defined = |name: &str| {
+            existing.contains(&format!("fn {name}(")) || existing.contains(&format!("fn {name}<"))
+        };
+
+        let changed = |name: &str| {
+            Error::Append(format!(
+                "function `{name}` has changed, regenerate the module without --append"
+            ))
+        };
+
+        let mut constants = Vec::new();
+        let mut types = Vec::new();
+        let mut messages = Vec::new();
+        let mut added = Vec::new();
+
+        for function in &model.functions {
+            if is_present(&function.selector) {
+                continue;
+            }
+
+            if is_defined(&function.ident.snake) {
+                return Err(changed(&function.name));
+            }
+
+            let function = std::slice::from_ref(function);
+            self.render_chunks("constant", module, function, &mut constants)?;
+            if !function[0].packed.is_empty() {
+                self.render_chunks("packed_args", module, function, &mut types)?;
+            }
+            self.render_chunks("message", module, function, &mut messages)?;
+            added.push(function[0].selector.clone());
+        }
+
+        for function in &model.overloaded_functions {
+            let present = function
+                .variants
+                .iter()
+                .filter(|variant| is_present(&variant.selector))
+                .count();
+
+            if present == function.variants.len() {
+                continue;
+            }
+
+            if present > 0 || is_defined(&function.ident.snake) {
+                return Err(changed(&function.name));
+            }
+
+            let function = std::slice::from_ref(function);
+            self.render_chunks("overloaded_args", module, function, &mut types)?;
+            self.render_chunks("overloaded_message", module, function, &mut messages)?;
+            added.extend(function[0].variants.iter().map(|v| v.selector.clone()));
+        }
+
+        // Structs are shared by messages, so only the missing ones are added
+        for definition in &model.structs {
+            if !existing.contains(&format!("pub struct {} ", definition.name)) {
+                let definition = std::slice::from_ref(definition);
+                self.render_chunks("struct", module, definition, &mut types)?;
+            }
+        }
+
+        let section = |name| self.template.render(name, module);
+        let as_text = |mut chunks: Vec<u8>| {
+            if !chunks.is_empty() {
+                chunks.push(b'\n');
+            }
+
+            String::from_utf8(chunks).expect("templates render valid UTF-8")
+        };
+
+        let epilogue = section("epilogue")?;
+        let types_anchor = match section("impl")? {
+            text if text.trim().is_empty() => epilogue.clone(),
+            text => text,
+        };
+
+        let updated = append::insert_before(existing, &section("storage")?, &as_text(constants))?;
+        let updated = append::insert_before(&updated, &types_anchor, &as_text(types))?;
+        let updated = append::insert_before(&updated, &epilogue, &as_text(messages))?;
+
+        Ok((stamp::replace(&updated, &module.abi_hash), added))
+    }
+
+    /// Renders library of the crate, named `name`, which defines the EVM types
+    /// and conversions for the modules generated with [`Options::shared_types`]
+    pub fn render_types(&self, name: &str, ink: &InkCrates) -> Result<String, Error> {
+        let context = SharedTypes {
+            shared_types: Some(name),
+            ink,
+        };
+
+        Ok(self.template.render("types_crate", &context)?)
+    }
+
+    /// Renders router of the namespaced modules to the writer section by section
+    pub fn render_router_to(
+        &self,
+        options: &Options,
+        models: &[Model],
+        writer: &mut dyn Write,
+    ) -> Result<(), Error> {
+        let router = Router {
+            module_name: &options.module_name,
+            evm_id: &options.evm_id,
+            para_id: options.para_id,
+            imports: &options.imports,
+            attributes: &options.attributes,
+            storage: &options.storage,
+            caller: options.caller.as_ref().map(|caller| caller.mapping),
+            account_mapping: options.native_types,
+            trait_name: None,
+            shared_types: None,
+            targets: models.iter().map(|model| &model.module).collect(),
+            // Storage of the router has no gas limit field
+            xvm: Xvm::new(options.xvm_version, false),
+            ink: &options.ink,
+        };
+
+        self.render_section("prologue", &router, writer)?;
+        for model in models {
+            self.render_chunks("constant", &model.module, &model.functions, writer)?;
+        }
+
+        self.render_section("router_storage", &router, writer)?;
+        self.render_section("account_address", &router, writer)?;
+        if models.iter().any(|model| model.module.typed_errors) {
+            self.render_section("call_error", &router, writer)?;
+        }
+        for model in models {
+            let functions = &model.overloaded_functions;
+            if !model.errors.is_empty() {
+                self.render_section("custom_error", model, writer)?;
+            }
+            self.render_events(&model.module, &model.events, writer)?;
+            self.render_chunks("struct", &model.module, &model.structs, writer)?;
+            self.render_chunks("overloaded_args", &model.module, functions, writer)?;
+            self.render_chunks(
+                "packed_args",
+                &model.module,
+                &model.packed_functions(),
+                writer,
+            )?;
+        }
+
+        if self.calls_xvm {
+            self.render_section(router.xvm.call, &router, writer)?;
+        }
+        self.render_section("router_impl", &router, writer)?;
+        for model in models {
+            let functions = &model.overloaded_functions;
+            self.render_chunks("overloaded_message", &model.module, functions, writer)?;
+            self.render_chunks("message", &model.module, &model.functions, writer)?;
+        }
+
+        self.render_section("epilogue", &router, writer)?;
+
+        Ok(())
+    }
+
+    fn render_section<C: Serialize>(
+        &self,
+        name: &str,
+        context: &C,
+        writer: &mut dyn Write,
+    ) -> Result<(), Error> {
+        writer.write_all(self.template.render(name, context)?.as_bytes())?;
+        Ok(())
+    }
+
+    fn render_events(
+        &self,
+        module: &Module,
+        events: &[Event],
+        writer: &mut dyn Write,
+    ) -> Result<(), Error> {
+        for event in events {
+            let chunk = EventChunk { module, event };
+            writer.write_all(self.template.render("event", &chunk)?.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn render_chunks<F: Serialize>(
+        &self,
+        name: &str,
+        module: &Module,
+        functions: &[F],
+        writer: &mut dyn Write,
+    ) -> Result<(), Error> {
+        for function in functions {
+            let chunk = Chunk { module, function };
+            writer.write_all(self.template.render(name, &chunk)?.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selectors_of_tuple_parameters() {
+        // Uniswap V3 `SwapRouter.exactInputSingle` and Multicall3 `aggregate3`
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "exactInputSingle", "inputs": [
+                    {"name": "params", "type": "tuple", "components": [
+                        {"type": "address"}, {"type": "address"}, {"type": "uint24"},
+                        {"type": "address"}, {"type": "uint256"}, {"type": "uint256"},
+                        {"type": "uint256"}, {"type": "uint160"}
+                    ]}
+                ]},
+                {"type": "function", "name": "aggregate3", "inputs": [
+                    {"name": "calls", "type": "tuple[]", "components": [
+                        {"type": "address"}, {"type": "bool"}, {"type": "bytes"}
+                    ]}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let selectors = abi
+            .members()
+            .map(|item| selector(&signature(item)).encode_hex::<String>())
+            .collect_vec();
+
+        assert_eq!(selectors, ["414bf389", "82ad56cb"]);
+        assert_eq!(signature(&abi[1]), "aggregate3((address,bool,bytes)[])");
+    }
+
+    #[test]
+    fn integers_take_smallest_native_type() {
+        let types = [
+            "uint8", "uint24", "uint128", "uint160", "int16", "int40", "int256",
+        ]
+        .map(|ty| convert_type(&ethabi::param_type::Reader::read(ty).unwrap()));
+
+        assert_eq!(types, ["u8", "u32", "u128", "U256", "i16", "i64", "I256"]);
+    }
+
+    #[test]
+    fn events_mark_indexed_fields_as_topics() {
+        let abi = json::parse(
+            r#"[
+                {"type": "event", "name": "Transfer", "inputs": [
+                    {"name": "from", "type": "address", "indexed": true},
+                    {"name": "to", "type": "address", "indexed": true},
+                    {"name": "value", "type": "uint256", "indexed": false},
+                    {"name": "type", "type": "uint8", "indexed": false}
+                ]},
+                {"type": "event", "name": "Transfer", "anonymous": true, "inputs": [
+                    {"name": "", "type": "string", "indexed": true},
+                    {"name": "a", "type": "uint8", "indexed": true},
+                    {"name": "b", "type": "uint8", "indexed": true},
+                    {"name": "c", "type": "uint8", "indexed": true}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let mut warnings = Vec::new();
+        let events = generator
+            .events(&abi, &Options::default(), &mut warnings)
+            .unwrap();
+        let fields = |event: &Event| {
+            event
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.rust_type.as_str(), field.topic))
+                .collect_vec()
+        };
+
+        assert_eq!(events[0].ident.upper_camel, "Transfer");
+        assert_eq!(
+            fields(&events[0]),
+            [
+                ("from", "H160", true),
+                ("to", "H160", true),
+                ("value", "U256", false),
+                ("r#type", "u8", false)
+            ]
+        );
+
+        assert_eq!(events[1].ident.upper_camel, "Transfer2");
+        assert_eq!(
+            fields(&events[1]),
+            [
+                ("field_0", "FixedBytes<32>", true),
+                ("a", "u8", true),
+                ("b", "u8", true),
+                ("c", "u8", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_of_discarded_overload_outputs() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "get", "stateMutability": "nonpayable", "inputs": [],
+                    "outputs": [{"type": "bool"}]},
+                {"type": "function", "name": "get", "stateMutability": "nonpayable",
+                    "inputs": [{"name": "id", "type": "uint8"}], "outputs": [{"type": "uint256"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "getter".to_owned(),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let model = generator.model(&abi, &options).unwrap();
+
+        assert!(matches!(
+            model.warnings(),
+            [Warning::DiscardedOutput { signature, .. }] if signature == "get(uint8)"
+        ));
+    }
+
+    #[test]
+    fn xvm_versions_shape_the_call() {
+        let abi = json::parse(
+            r#"[{"type": "function", "name": "mint", "stateMutability": "nonpayable", "inputs": [],
+                "outputs": [{"type": "uint256"}]}]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let render = |xvm_version| {
+            let options = Options {
+                module_name: "token".to_owned(),
+                xvm_version,
+                ..Default::default()
+            };
+            let model = generator.model(&abi, &options).unwrap();
+
+            let mut code = Vec::new();
+            generator.render_to(&model, &mut code).unwrap();
+            (model, String::from_utf8(code).unwrap())
+        };
+
+        let (model, code) = render(XvmVersion::ChainExtension);
+        assert_eq!(model.functions[0].output, "Option<U256>");
+        assert!(code.contains("#[ink::contract(env = xvm_environment::XvmDefaultEnvironment)]"));
+        assert!(!code.contains("pub trait XvmExtension"));
+
+        let (model, code) = render(XvmVersion::V2);
+        assert!(model.functions[0].returns.is_none());
+        assert_eq!(model.functions[0].output, "bool");
+        assert!(code.contains("#[ink::contract(env = crate::XvmEnvironment)]"));
+        assert!(code.contains(") -> Result<(), XvmError>;"));
+
+        let (model, code) = render(XvmVersion::V3);
+        assert_eq!(model.functions[0].output, "Option<U256>");
+        assert!(code.contains("gas_limit: Option<u64>,"));
+        assert!(code.contains(".xvm_call(evm_id, Vec::from(address.0.as_ref()), input, None)"));
+    }
+
+    #[test]
+    fn forwards_stored_gas_limit() {
+        let abi = json::parse(
+            r#"[{"type": "function", "name": "mint", "stateMutability": "nonpayable", "inputs": []}]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let render = |xvm_version| {
+            let options = Options {
+                module_name: "token".to_owned(),
+                xvm_version,
+                gas_limit_setter: true,
+                ..Default::default()
+            };
+            let model = generator.model(&abi, &options).unwrap();
+
+            let mut code = Vec::new();
+            generator.render_to(&model, &mut code).unwrap();
+            String::from_utf8(code).unwrap()
+        };
+
+        let code = render(XvmVersion::V3);
+        assert!(code.contains("gas_limit: Option<u64>,"));
+        assert!(code.contains("pub fn set_gas_limit(&mut self, gas_limit: Option<u64>)"));
+        assert!(code.contains("input, self.gas_limit)"));
+
+        // The call has nowhere to pass it
+        assert!(!render(XvmVersion::V2).contains("fn set_gas_limit"));
+    }
+
+    #[test]
+    fn generates_ink_4_and_5() {
+        let abi = json::parse(
+            r#"[{"type": "function", "name": "pause", "stateMutability": "nonpayable", "inputs": []}]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let render = |major, xvm_version| {
+            let options = Options {
+                module_name: "pausable".to_owned(),
+                signature_helpers: true,
+                xvm_version,
+                ink: InkCrates::of(major).unwrap(),
+                ..Default::default()
+            };
+
+            let mut code = Vec::new();
+            let model = generator.model(&abi, &options).unwrap();
+            generator.render_to(&model, &mut code).unwrap();
+            generator.render_tests_to(&model, &mut code).unwrap();
+            String::from_utf8(code).unwrap()
+        };
+
+        let code = render(3, XvmVersion::ChainExtension);
+        assert!(code.contains("use ink_lang as ink;"));
+        assert!(code.contains("use ink_prelude::{string::String, vec::Vec};"));
+
+        let code = render(4, XvmVersion::ChainExtension);
+        assert!(!code.contains("ink_"));
+        assert!(code.contains("use ink::prelude::{string::String, vec::Vec};"));
+        assert!(code.contains("ink::env::hash_bytes::<ink::env::hash::Keccak256>"));
+        assert!(code.contains("ink::env::test::register_chain_extension"));
+        assert!(code.contains("#[cfg_attr(feature = \"std\", derive(StorageLayout))]"));
+
+        let code = render(5, XvmVersion::V3);
+        assert!(!code.contains("ink_"));
+        assert!(code.contains("#[ink::chain_extension(extension = 0x0001)]"));
+        assert!(code.contains("#[ink(function = 0x0001)]"));
+        assert!(code.contains("fn ext_id(&self) -> u16"));
+    }
+
+    #[test]
+    fn renders_e2e_tests() {
+        let abi = json::parse(
+            r#"[{"type": "function", "name": "pause", "stateMutability": "nonpayable", "inputs": []}]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let render = |major| {
+            let options = Options {
+                module_name: "pausable".to_owned(),
+                ink: InkCrates::of(major).unwrap(),
+                ..Default::default()
+            };
+
+            let mut code = Vec::new();
+            let model = generator.model(&abi, &options).unwrap();
+            generator.render_e2e_tests_to(&model, &mut code).unwrap();
+            String::from_utf8(code).unwrap()
+        };
+
+        let code = render(4);
+        assert!(
+            code.contains("#[ink_e2e::test(environment = xvm_environment::XvmDefaultEnvironment)]")
+        );
+        assert!(code.contains("async fn pause_e2e(mut client: ink_e2e::Client<C, E>)"));
+        assert!(code.contains("PausableRef::new(H160::from(EVM_ADDRESS))"));
+        assert!(code.contains(".call(|wrapper| wrapper.pause())"));
+        assert!(code.ends_with("    }\n}\n"));
+
+        let code = render(5);
+        assert!(code.contains("async fn pause_e2e<Client: E2EBackend>(mut client: Client)"));
+        assert!(code.contains("let message = contract.call_builder::<Pausable>().pause();"));
+        assert!(code.contains(".submit()"));
+    }
+
+    #[test]
+    fn empty_reason_counts_filters() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": []},
+                {"type": "function", "name": "balanceOf", "stateMutability": "view", "inputs": []},
+                {"type": "function", "name": "totalSupply", "stateMutability": "view", "inputs": []}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            filter: Filter::new(vec!["*Of".to_owned(), "total*".to_owned()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            empty_reason(&abi, &options),
+            "of 3 functions 1 excluded by --only or --exclude, 2 read-only, see --include-views"
+        );
+        assert_eq!(
+            empty_reason(&json::parse("[]").unwrap(), &options),
+            "the ABI has no functions"
+        );
+    }
+
+    #[test]
+    fn nested_fixed_arrays() {
+        let ty = ethabi::param_type::Reader::read("bytes32[4][2]").unwrap();
+        assert_eq!(convert_type(&ty), "[[FixedBytes<32>; 4]; 2]");
+        assert_eq!(
+            param_type_expr(&ty),
+            "ParamType::FixedArray(Box::new(ParamType::FixedArray(Box::new(ParamType::FixedBytes(32)), 4)), 2)"
+        );
+        assert_eq!(
+            zero_value(&ty).unwrap(),
+            "[(); 2].map(|_| [(); 4].map(|_| FixedBytes([0u8; 32])))"
+        );
+        assert_eq!(tokenize_expr(&ty, "value"), "value.tokenize()");
+
+        let ty = ethabi::param_type::Reader::read("bytes[2][3]").unwrap();
+        assert_eq!(convert_type(&ty), "[[Vec<u8>; 2]; 3]");
+        assert_eq!(
+            tokenize_expr(&ty, "value"),
+            "Token::FixedArray(value.into_iter().map(|item| \
+             Token::FixedArray(item.into_iter().map(|item| Token::Bytes(item)).collect())).collect())"
+        );
+
+        let ty = ethabi::param_type::Reader::read("uint8[64]").unwrap();
+        assert_eq!(convert_type(&ty), "[u8; 64]");
+    }
+
+    #[test]
+    fn packs_too_many_arguments() {
+        let inputs = (0..=MAX_ARGUMENTS)
+            .map(|i| format!(r#"{{"name": "a{i}", "type": "uint8"}}"#))
+            .join(", ");
+        let abi = json::parse(&format!(
+            r#"[{{"type": "function", "name": "wide", "stateMutability": "nonpayable", "inputs": [{inputs}]}}]"#
+        ))
+        .unwrap();
+
+        let options = Options {
+            module_name: "wide".to_owned(),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let model = generator.model(&abi, &options).unwrap();
+
+        let function = &model.functions[0];
+        assert_eq!(function.packed.len(), MAX_ARGUMENTS + 1);
+        assert_eq!(function.arguments.len(), 1);
+        assert_eq!(function.arguments[0].rust_type, "WideArgs");
+        assert!(matches!(
+            model.warnings(),
+            [Warning::PackedArguments { count, .. }] if *count == MAX_ARGUMENTS + 1
+        ));
+    }
+
+    #[test]
+    fn reports_all_parameter_problems() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "a", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "x", "type": "uint8"},
+                    {"name": "y", "type": "foo"}
+                ]},
+                {"type": "function", "name": "b", "stateMutability": "nonpayable", "inputs": [
+                    {"type": "uint8"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "broken".to_owned(),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        assert!(matches!(
+            generator.model(&abi, &options),
+            Err(Error::Params(problems)) if matches!(
+                problems.as_slice(),
+                [ParamError::Unsupported { index: 1, .. }, ParamError::Malformed { index: 0, .. }]
+            )
+        ));
+    }
+
+    #[test]
+    fn names_struct_params() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "swap", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "params", "type": "tuple[]", "internalType": "struct Router.SwapParams[]",
+                        "components": [
+                            {"name": "tokenIn", "type": "address", "internalType": "address"},
+                            {"name": "type", "type": "uint8", "internalType": "uint8"}
+                        ]}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "router".to_owned(),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let model = generator.model(&abi, &options).unwrap();
+        assert_eq!(model.functions[0].inputs[0].rust_type, "Vec<SwapParams>");
+
+        let mut code = Vec::new();
+        generator.render_to(&model, &mut code).unwrap();
+        let code = String::from_utf8(code).unwrap();
+        assert!(code.contains("pub struct SwapParams {"));
+        assert!(code.contains("pub r#type: u8,"));
+    }
+
+    #[test]
+    fn names_overloads() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "safeTransferFrom", "inputs": [
+                    {"type": "address"}, {"type": "address"}, {"type": "uint256"}
+                ]},
+                {"type": "function", "name": "safeTransferFrom", "inputs": [
+                    {"type": "address"}, {"type": "address"}, {"type": "uint256"}, {"type": "bytes"}
+                ]},
+                {"type": "function", "name": "safeTransferFrom2", "inputs": []},
+                {"type": "function", "name": "safeTransferFrom2", "inputs": [{"type": "uint8[]"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let suffix = overload_names(Overloads::Suffix, abi.members());
+        assert_eq!(
+            suffix["safeTransferFrom(address,address,uint256,bytes)"],
+            "safeTransferFrom_address_address_uint256_bytes"
+        );
+        assert_eq!(
+            suffix["safeTransferFrom2(uint8[])"],
+            "safeTransferFrom2_uint8_array"
+        );
+
+        let index = overload_names(Overloads::Index, abi.members());
+        assert_eq!(
+            index["safeTransferFrom(address,address,uint256)"],
+            "safeTransferFrom"
+        );
+        assert_eq!(
+            index["safeTransferFrom(address,address,uint256,bytes)"],
+            "safeTransferFrom3"
+        );
+        assert!(overload_names(Overloads::Enum, abi.members()).is_empty());
+    }
+
+    #[test]
+    fn implements_trait_definition() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "approve", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "spender", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "bool"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "erc20".to_owned(),
+            trait_definition: true,
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let mut code = Vec::new();
+        generator
+            .render_to(&generator.model(&abi, &options).unwrap(), &mut code)
+            .unwrap();
+
+        let code = String::from_utf8(code).unwrap();
+        assert!(code.contains("pub trait Erc20Like {"));
+        assert!(code.contains("impl Erc20Like for Erc20 {"));
+        assert!(
+            code.contains("        fn approve(&mut self, spender: H160, amount: U256) -> bool {")
+        );
+    }
+
+    #[test]
+    fn returns_typed_errors() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "approve", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "spender", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "bool"}]},
+                {"type": "function", "name": "mint", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "uint256"}]},
+                {"type": "error", "name": "InsufficientBalance", "inputs": [
+                    {"name": "needed", "type": "uint256"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "erc20".to_owned(),
+            typed_errors: true,
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let mut code = Vec::new();
+        generator
+            .render_to(&generator.model(&abi, &options).unwrap(), &mut code)
+            .unwrap();
+
+        let code = String::from_utf8(code).unwrap();
+        assert!(code.contains("pub enum XvmCallError {"));
+        assert!(code.contains("amount: U256) -> Result<(), XvmCallError> {"));
+        assert!(code.contains("Err(XvmCallError::ReturnedFalse)"));
+        assert!(code.contains("pub enum Revert {"));
+        assert!(code.contains("pub enum Erc20Error {"));
+        assert!(
+            code.contains("        InsufficientBalance {\n            needed: U256,\n        },")
+        );
+        assert!(code.contains("fn mint(&mut self, amount: U256) -> Result<U256, XvmCallError> {"));
+    }
+
+    #[test]
+    fn overrides_types() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "bool"}]},
+                {"type": "function", "name": "totalSupply", "stateMutability": "nonpayable", "inputs": [],
+                    "outputs": [{"type": "uint256"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut options = Options {
+            module_name: "erc20".to_owned(),
+            type_overrides: TypeOverrides {
+                types: vec![(ParamType::Uint(256), "u128".to_owned())],
+                params: BTreeMap::from([(
+                    "transfer".to_owned(),
+                    BTreeMap::from([("to".to_owned(), "AccountId".to_owned())]),
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let model = generator.model(&abi, &options).unwrap();
+        let inputs = &model.functions[0].inputs;
+        assert_eq!(inputs[0].rust_type, "AccountId");
+        assert_eq!(inputs[0].tokenize, "account_address(&to).tokenize()");
+        assert_eq!(inputs[1].rust_type, "u128");
+        assert_eq!(inputs[1].tokenize, "Token::Uint(amount.into())");
+        assert_eq!(
+            model.functions[1].returns.as_ref().unwrap().rust_type,
+            "u128"
+        );
+
+        options.type_overrides.types = vec![(ParamType::Address, "u8".to_owned())];
+        assert!(matches!(
+            generator.model(&abi, &options),
+            Err(Error::Params(problems))
+                if matches!(problems[..], [ParamError::UnsupportedOverride { .. }])
+        ));
+    }
+
+    #[test]
+    fn imports_shared_types() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "approve", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "spender", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "bool"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "erc20".to_owned(),
+            shared_types: Some("evm_types".to_owned()),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let mut code = Vec::new();
+        generator
+            .render_to(&generator.model(&abi, &options).unwrap(), &mut code)
+            .unwrap();
+
+        let code = String::from_utf8(code).unwrap();
+        assert!(code.contains("pub use evm_types::{FixedBytes, H160, I256, U256};"));
+        assert!(!code.contains("pub struct H160"));
+
+        let types = generator
+            .render_types("evm_types", &InkCrates::default())
+            .unwrap();
+        assert!(types.contains("pub struct H160(pub [u8; 20]);"));
+        assert!(types.contains("    pub trait Tokenize {"));
+    }
+
+    #[test]
+    fn requires_erc20_for_psp22() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "transfer", "stateMutability": "nonpayable", "inputs": [
+                    {"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}
+                ], "outputs": [{"type": "bool"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let options = Options {
+            module_name: "token".to_owned(),
+            psp22: Some(CallerAddress { hashed: false }),
+            ..Default::default()
+        };
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        assert!(matches!(
+            generator.model(&abi, &options),
+            Err(Error::Adapter { reason, .. }) if reason.contains("totalSupply()")
+        ));
+    }
+
+    #[test]
+    fn detects_enumerable_erc721() {
+        let abi = json::parse(
+            r#"[
+                {"type": "function", "name": "balanceOf", "inputs": [{"type": "address"}]},
+                {"type": "function", "name": "ownerOf", "inputs": [{"type": "uint256"}]},
+                {"type": "function", "name": "approve", "inputs": [{"type": "address"}, {"type": "uint256"}]},
+                {"type": "function", "name": "getApproved", "inputs": [{"type": "uint256"}]},
+                {"type": "function", "name": "setApprovalForAll", "inputs": [{"type": "address"}, {"type": "bool"}]},
+                {"type": "function", "name": "isApprovedForAll", "inputs": [{"type": "address"}, {"type": "address"}]},
+                {"type": "function", "name": "transferFrom", "inputs": [
+                    {"type": "address"}, {"type": "address"}, {"type": "uint256"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let generator = Generator::new(&Target::AstarXvm, &CustomTemplates::default()).unwrap();
+        let signatures = generator
+            .adapted_functions(&abi, "psp34", "ERC721", ERC721_FUNCTIONS)
+            .unwrap();
+        assert!(!signatures.contains("totalSupply()"));
+        assert!(generator
+            .adapted_functions(&abi, "psp22", "ERC20", ERC20_FUNCTIONS)
+            .is_err());
+    }
 }
@@ -0,0 +1,66 @@
+use crate::{error::Error, normalize};
+use hex::ToHex;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::path::Path;
+
+/// Declaration of the hash in the generated module, followed by the hex value
+const PREFIX: &str = "SOURCE_ABI_HASH: [u8; 32] = hex![\"";
+
+/// Keccak-256 hash of the canonical form of the ABI, so reformatting
+/// or reordering the ABI does not change it
+pub fn abi_hash(abi: Value) -> Result<String, Error> {
+    let canonical = normalize::normalize(abi)?.to_string();
+    Ok(Keccak256::digest(canonical.as_bytes()).encode_hex())
+}
+
+/// Hash of the ABI the module was generated from
+pub fn read(module: &str) -> Option<&str> {
+    let start = module.find(PREFIX)? + PREFIX.len();
+    module.get(start..start + 64)
+}
+
+/// Replaces the hash of the module, leaving the rest of it untouched
+pub fn replace(module: &str, hash: &str) -> String {
+    match module.find(PREFIX) {
+        Some(start) if read(module).is_some() => {
+            let start = start + PREFIX.len();
+            [&module[..start], hash, &module[start + 64..]].concat()
+        }
+        _ => module.to_owned(),
+    }
+}
+
+/// Checks that the module was generated from the ABI
+pub fn check(abi: Value, path: &Path, module: &str) -> Result<(), Error> {
+    let expected = abi_hash(abi)?;
+    match read(module) {
+        Some(found) if found == expected => Ok(()),
+        Some(found) => Err(Error::StaleModule {
+            path: path.to_owned(),
+            found: found.to_owned(),
+            expected,
+        }),
+        None => Err(Error::MissingStamp(path.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_and_replaces_hash() {
+        let abi = json!([{"type": "function", "name": "transfer", "inputs": []}]);
+        let hash = abi_hash(abi.clone()).unwrap();
+        let module = format!("    pub const SOURCE_ABI_HASH: [u8; 32] = hex![\"{hash}\"];\n");
+
+        assert_eq!(read(&module), Some(hash.as_str()));
+        assert!(check(abi, Path::new("erc20.rs"), &module).is_ok());
+
+        let other = abi_hash(json!([])).unwrap();
+        assert_eq!(read(&replace(&module, &other)), Some(other.as_str()));
+        assert_eq!(replace("mod erc20 {}", &other), "mod erc20 {}");
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::Error;
+use std::fmt;
+
+/// Why a function of the ABI is not wrapped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// `view` or `pure` function, see `--include-views`
+    ReadOnly,
+
+    /// Returns more values than a tuple the module can decode
+    TooManyOutputs,
+
+    /// Takes a storage reference of a Solidity library
+    StorageReference,
+
+    /// State mutability the wrapper does not know
+    UnknownMutability,
+}
+
+/// Non-fatal finding of the generation, returned along with the model,
+/// so the generated code could be used but deserves a look
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// ABI describes a Solidity library, whose state modifying functions revert
+    Library { module: String },
+
+    /// Functions without state mutability, treated as `assumed`
+    MissingMutability {
+        module: String,
+        count: usize,
+        assumed: &'static str,
+    },
+
+    SkippedFunction {
+        module: String,
+        signature: String,
+        reason: SkipReason,
+    },
+
+    /// Parameter taken as a type that does not keep its whole value
+    LossyType {
+        module: String,
+        item: String,
+        parameter: String,
+        evm_type: String,
+        rust_type: String,
+    },
+
+    /// Arguments of the message taken as a single `{Name}Args` struct
+    PackedArguments {
+        module: String,
+        signature: String,
+        count: usize,
+    },
+
+    /// Encoded arguments do not fit the buffer ink! decodes message input from
+    LargeArguments {
+        module: String,
+        signature: String,
+        size: usize,
+        limit: usize,
+    },
+
+    /// Overloaded function whose message only reports whether the call succeeded
+    DiscardedOutput { module: String, signature: String },
+
+    /// Item or parameter generated under a different name than in the ABI
+    Renamed {
+        module: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 4] = [
+        SkipReason::ReadOnly,
+        SkipReason::TooManyOutputs,
+        SkipReason::StorageReference,
+        SkipReason::UnknownMutability,
+    ];
+
+    /// Functions skipped for the reason when counted, i.e. `2 read-only`
+    pub fn description(self) -> &'static str {
+        match self {
+            SkipReason::ReadOnly => "read-only",
+            SkipReason::TooManyOutputs => "returning more than 8 values",
+            SkipReason::StorageReference => "taking storage references",
+            SkipReason::UnknownMutability => "with unknown state mutability",
+        }
+    }
+}
+
+/// Fails with every function the warnings report as skipped, if any
+pub fn strict(warnings: &[Warning]) -> Result<(), Error> {
+    let skipped = warnings
+        .iter()
+        .filter(|warning| matches!(warning, Warning::SkippedFunction { .. }))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if skipped.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SkippedFunctions(skipped))
+    }
+}
+
+/// One line summary of the functions the warnings report as skipped, if any
+pub fn skip_summary(warnings: &[Warning]) -> Option<String> {
+    let mut module = None;
+    let mut counts: Vec<(SkipReason, usize)> = Vec::new();
+    for warning in warnings {
+        if let Warning::SkippedFunction {
+            module: skipped_module,
+            reason,
+            ..
+        } = warning
+        {
+            module = Some(skipped_module);
+            match counts.iter_mut().find(|(other, _)| other == reason) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*reason, 1)),
+            }
+        }
+    }
+
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    let reasons = counts
+        .iter()
+        .map(|(reason, count)| format!("{count} {}", reason.description()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    module.map(|module| format!("{total} functions of {module} are not wrapped: {reasons}"))
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Library { module } => write!(
+                f,
+                "{module} is a Solidity library; functions taking storage references are skipped \
+                 and the rest revert unless the library is called with DELEGATECALL, which XVM does not support"
+            ),
+
+            Warning::MissingMutability {
+                module,
+                count,
+                assumed,
+            } => write!(
+                f,
+                "{count} functions of {module} have no state mutability and are treated as {assumed}, \
+                 see `missing_mutability` in the [abi] section of the config"
+            ),
+
+            Warning::SkippedFunction {
+                module,
+                signature,
+                reason,
+            } => {
+                let reason = match reason {
+                    SkipReason::ReadOnly => "it is read-only, see --include-views",
+                    SkipReason::TooManyOutputs => "it returns more than 8 values",
+                    SkipReason::StorageReference => "it takes a storage reference",
+                    SkipReason::UnknownMutability => "its state mutability is unknown",
+                };
+
+                write!(f, "`{signature}` of {module} is skipped because {reason}")
+            }
+
+            Warning::LossyType {
+                module,
+                item,
+                parameter,
+                evm_type,
+                rust_type,
+            } => write!(
+                f,
+                "`{parameter}` of `{item}` of {module} is `{evm_type}`, but is taken as `{rust_type}`"
+            ),
+
+            Warning::PackedArguments {
+                module,
+                signature,
+                count,
+            } => write!(
+                f,
+                "`{signature}` of {module} takes {count} arguments, too many for a message, \
+                 so they are packed into a struct"
+            ),
+
+            Warning::LargeArguments {
+                module,
+                signature,
+                size,
+                limit,
+            } => write!(
+                f,
+                "arguments of `{signature}` of {module} take at least {size} bytes encoded, \
+                 more than the {limit} bytes ink! decodes messages from"
+            ),
+
+            Warning::DiscardedOutput { module, signature } => write!(
+                f,
+                "`{signature}` of {module} is overloaded and its output is discarded, \
+                 see --overloads to generate a message per overload"
+            ),
+
+            Warning::Renamed { module, from, to } => {
+                write!(f, "`{from}` of {module} is generated as `{to}`")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skipped(signature: &str, reason: SkipReason) -> Warning {
+        Warning::SkippedFunction {
+            module: "erc20".to_owned(),
+            signature: signature.to_owned(),
+            reason,
+        }
+    }
+
+    #[test]
+    fn summarizes_skipped_functions() {
+        let warnings = [
+            skipped("balanceOf(address)", SkipReason::ReadOnly),
+            Warning::Library {
+                module: "erc20".to_owned(),
+            },
+            skipped("reserves()", SkipReason::TooManyOutputs),
+            skipped("totalSupply()", SkipReason::ReadOnly),
+        ];
+
+        assert_eq!(
+            skip_summary(&warnings).as_deref(),
+            Some(
+                "3 functions of erc20 are not wrapped: 2 read-only, 1 returning more than 8 values"
+            )
+        );
+        assert_eq!(skip_summary(&warnings[1..2]), None);
+    }
+
+    #[test]
+    fn strict_fails_on_skipped_functions() {
+        let library = Warning::Library {
+            module: "erc20".to_owned(),
+        };
+        assert!(strict(&[library.clone()]).is_ok());
+
+        let reserves = skipped("reserves()", SkipReason::TooManyOutputs);
+        assert!(matches!(
+            strict(&[library, reserves.clone()]),
+            Err(Error::SkippedFunctions(skipped)) if skipped == [reserves]
+        ));
+    }
+}
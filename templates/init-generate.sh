@@ -0,0 +1,41 @@
+#!/bin/sh
+# Generates an ink! contract wrapping every ABI of the abis/ directory. Every
+# wrapper is a separate `cargo contract` project in contracts/<name>/, build it
+# with `cargo contract build --manifest-path contracts/<name>/Cargo.toml`.
+#
+# The manifest is written only once and may be edited, the module is
+# regenerated on every run with its sumi:keep regions preserved.
+#
+#     sh generate.sh [sumi options]
+set -e
+
+for abi in abis/*.json abis/*.abi; do
+    [ -e "$abi" ] || continue
+
+    name=$(basename "${abi%.*}" | sed 's/[^A-Za-z0-9]/_/g' | tr 'A-Z' 'a-z')
+    dir="contracts/$name"
+    mkdir -p "$dir"
+
+    if [ ! -e "$dir/Cargo.toml" ]; then
+        cat > "$dir/Cargo.toml" <<MANIFEST
+[package]
+name = "$name"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+name = "$name"
+path = "lib.rs"
+crate-type = ["cdylib", "rlib"]
+
+[features]
+default = ["std"]
+std = []
+ink-as-dependency = []
+MANIFEST
+        sumi --write-deps "$dir/Cargo.toml" "$@"
+    fi
+
+    sumi --input "$abi" --output "$dir/lib.rs" --module-name "$name" "$@"
+    echo "$abi -> $dir/lib.rs"
+done